@@ -0,0 +1,329 @@
+use super::{SourceMap, display_width, expand_tabs};
+use crate::shared::{Diagnostic, DiagnosticRecord, Error, ErrorPos, ErrorReporter, Label, Locale, Severity, SourceFile, source_id_uri};
+use std::fmt::Write;
+use std::io::{self, IsTerminal};
+
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderOptions {
+	pub color: bool,
+	pub tab_width: usize,
+	/// Сколько строк многострочного диапазона показывать с каждого края; середина сворачивается в `...`
+	pub edge_lines: usize,
+	pub locale: Locale,
+}
+
+impl RenderOptions {
+	pub const PLAIN: Self = Self {
+		color: false,
+		tab_width: 4,
+		edge_lines: 2,
+		locale: Locale::En,
+	};
+	pub const COLORED: Self = Self { color: true, ..Self::PLAIN };
+
+	/// Цвет для `stdout`: вывод не перенаправлен и не задан `NO_COLOR`. Решает вызывающий, рендер окружение не читает
+	pub fn color_from_env() -> bool {
+		Self::color_from_vars(|name| std::env::var(name).ok(), io::stdout().is_terminal())
+	}
+
+	pub fn color_from_vars(var: impl Fn(&str) -> Option<String>, terminal: bool) -> bool {
+		terminal && var("NO_COLOR").is_none_or(|value| value.is_empty())
+	}
+}
+
+impl Default for RenderOptions {
+	fn default() -> Self {
+		Self::PLAIN
+	}
+}
+
+/// Ошибка с фрагментом исходника. Основной диапазон подчёркивается `^`, вторичные — `-`:
+///
+/// ```text
+/// error[U0013]: Semantic error: Rate `kJ/s` does not match `<Mass> ÷ delta <Time>`
+///  --> rates.lacon:2:15
+///   |
+/// 2 | consumption = 5kJ / delta 1s
+///   |               ^^^ expected a mass rate
+/// 1 | consumption<Expr>: <Mass> ÷ delta <Time>
+///   |                    ------ declared here
+///  ::: units.lacon:3:1: `kJ` declared here
+///   = note: rates are compared by dimension
+/// ```
+pub fn render_error(error: &Error, source: &SourceFile, options: &RenderOptions) -> String {
+	render_diagnostic(error, Severity::Error, source, options)
+}
+
+/// То же с заголовком и цветом по важности: `warning[U0009]: ...`
+pub fn render_diagnostic(error: &Error, severity: Severity, source: &SourceFile, options: &RenderOptions) -> String {
+	let paint = |color: &'static str| if options.color { color } else { "" };
+	let (blue, bold, reset) = (paint(BLUE), paint(BOLD), paint(RESET));
+	let accent = match severity {
+		Severity::Error => paint(RED),
+		Severity::Warning => paint(YELLOW),
+		Severity::Info | Severity::Hint => blue,
+	};
+
+	let mut out = format!("{}{}[{}]{}{}: {}{}\n", accent, severity, error.kind.code(), reset, bold, error.kind.message(options.locale), reset);
+	let Some(pos) = &error.pos else {
+		let _ = writeln!(out, " {}-->{} {}", blue, reset, source.full_path());
+		write_trailer(&mut out, error, options.locale, 1, blue, reset);
+		return out;
+	};
+
+	let map = SourceMap::new(&source.source);
+	let (local, foreign): (Vec<&Label>, Vec<&Label>) = error.secondary.iter().partition(|label| label.source.as_ref().is_none_or(|id| *id == source.source_id));
+	let spans = std::iter::once(pos).chain(local.iter().map(|label| &label.pos));
+	let gutter = spans.map(|pos| map.line_of(byte_range(pos, &source.source).1.saturating_sub(1))).max().unwrap_or(1).to_string().len();
+
+	let _ = writeln!(out, "{:gutter$}{}-->{} {}:{}:{}", "", blue, reset, source.full_path(), pos.start.line, pos.start.column);
+	let _ = writeln!(out, "{:gutter$} {}|{}", "", blue, reset);
+
+	let frame = Frame {
+		map: &map,
+		text: &source.source,
+		options,
+		gutter,
+		blue,
+		reset,
+	};
+	frame.write_span(&mut out, pos, '^', accent, error.label.as_deref());
+	for label in local {
+		frame.write_span(&mut out, &label.pos, '-', blue, Some(&label.message));
+	}
+	for label in foreign {
+		let path = label.source.as_ref().map(source_id_uri).unwrap_or_default();
+		let _ = writeln!(out, "{:gutter$}{}:::{} {}:{}:{}: {}", "", blue, reset, path, label.pos.start.line, label.pos.start.column, label.message);
+	}
+
+	write_trailer(&mut out, error, options.locale, gutter, blue, reset);
+	out
+}
+
+struct Frame<'a> {
+	map: &'a SourceMap<'a>,
+	text: &'a str,
+	options: &'a RenderOptions,
+	gutter: usize,
+	blue: &'a str,
+	reset: &'a str,
+}
+
+impl Frame<'_> {
+	/// Строки диапазона с подчёркиванием; подпись — после подчёркивания последней строки
+	fn write_span(&self, out: &mut String, pos: &ErrorPos, marker: char, color: &str, label: Option<&str>) {
+		let (gutter, blue, reset, tab_width) = (self.gutter, self.blue, self.reset, self.options.tab_width);
+		let (start, end) = byte_range(pos, self.text);
+		let (first, last) = (self.map.line_of(start), self.map.line_of(end.saturating_sub(1).max(start)));
+
+		let edge = self.options.edge_lines.max(1);
+		for line in first..=last {
+			if last - first + 1 > edge * 2 + 1 && line == first + edge {
+				let _ = writeln!(out, "{}...{}", blue, reset);
+			}
+			if last - first + 1 > edge * 2 + 1 && line >= first + edge && line <= last - edge {
+				continue;
+			}
+
+			let (Some(content), Some(line_start)) = (self.map.line(line), self.map.line_start(line)) else {
+				continue;
+			};
+			// Продолжение диапазона подчёркивается от первого непробельного символа
+			let from = if line == first { start - line_start } else { content.len() - content.trim_start().len() };
+			let to = if line == last { (end - line_start).min(content.len()) } else { content.len() };
+			let (from_col, to_col) = (display_width(content, from, tab_width), display_width(content, to, tab_width));
+			let underline = marker.to_string().repeat(to_col.saturating_sub(from_col).max(1));
+			let label = label.filter(|_| line == last).map(|label| format!(" {}", label)).unwrap_or_default();
+
+			let _ = writeln!(out, "{}{:>gutter$} |{} {}", blue, line, reset, expand_tabs(content, tab_width));
+			let _ = writeln!(out, "{:gutter$} {}|{} {:from_col$}{}{}{}{}", "", blue, reset, "", color, underline, label, reset);
+		}
+	}
+}
+
+// Пустой диапазон подчёркивает один символ под началом. Смещения прижимаются к началу символа:
+// позиция из другой версии файла не должна ронять вывод
+fn byte_range(pos: &ErrorPos, text: &str) -> (usize, usize) {
+	let start = char_boundary(text, pos.start.offset);
+	let end = pos
+		.end
+		.map(|end| char_boundary(text, end.offset))
+		.filter(|end| *end > start)
+		.unwrap_or_else(|| start + text.get(start..).and_then(|rest| rest.chars().next()).map_or(0, char::len_utf8));
+	(start, end)
+}
+
+fn char_boundary(text: &str, offset: usize) -> usize {
+	let mut offset = offset.min(text.len());
+	while !text.is_char_boundary(offset) {
+		offset -= 1;
+	}
+	offset
+}
+
+fn write_trailer(out: &mut String, error: &Error, locale: Locale, gutter: usize, blue: &str, reset: &str) {
+	for note in &error.notes {
+		let _ = writeln!(out, "{:gutter$} {}={} note: {}", "", blue, reset, note);
+	}
+	for suggestion in &error.suggestions {
+		let _ = writeln!(out, "{:gutter$} {}={} help: {}", "", blue, reset, suggestion.localized(locale));
+	}
+}
+
+impl ErrorReporter {
+	/// Как `report`, но с фрагментом кода; `color` — для копии в терминал, см. `RenderOptions::color_from_env`
+	pub fn report_in(&self, error: &Error, source: &SourceFile, color: bool) -> io::Result<()> {
+		let options = RenderOptions {
+			color,
+			locale: self.locale(),
+			..RenderOptions::PLAIN
+		};
+		self.report_diagnostics([framed(error, Severity::Error, source, &options)])
+	}
+}
+
+/// Диагностика с фрагментом кода для любого приёмника. Текст для файлов всегда без цвета;
+/// цветная копия для терминала рендерится, только если её просят в `options`
+pub fn framed(error: &Error, severity: Severity, source: &SourceFile, options: &RenderOptions) -> Diagnostic {
+	let plain = render_diagnostic(error, severity, source, &RenderOptions { color: false, ..*options });
+	let diagnostic = Diagnostic::new(DiagnosticRecord::localized(error, severity, Some(source), options.locale), plain.trim_end().to_string());
+	match options.color {
+		true => diagnostic.with_console(render_diagnostic(error, severity, source, options).trim_end().to_string()),
+		false => diagnostic,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::frontend::lexer::{Scanner, check_unit_suffixes};
+	use crate::shared::{ErrorKind, ErrorReporterFlag, ErrorStorage, LexicalError, Position, SemanticError, SourceId, UnitContext, UnitError, lsp_diagnostic, sarif_log};
+
+	fn frame_source() -> SourceFile {
+		SourceFile::as_virtual("frame.lacon", "let a = 1\n\tname = \"abc\n速度 = 5 x\n")
+	}
+
+	fn rate_source() -> SourceFile {
+		SourceFile::as_virtual("rates.lacon", "consumption<Expr>: <Mass> ÷ delta <Time>\nconsumption = 5kJ / delta 1s\n")
+	}
+
+	/// Подписи в том же файле и в другом, плюс заметка
+	fn rate_mismatch() -> Error {
+		Error::span(
+			ErrorKind::Semantic(SemanticError::Unit(UnitError::RateMismatch("kJ/s".into(), "<Mass> ÷ delta <Time>".into()))),
+			Position::new(2, 15, 56),
+			Position::new(2, 18, 59),
+		)
+		.with_label("expected a mass rate")
+		.with_secondary(Position::new(1, 20, 19), Some(Position::new(1, 26, 25)), "declared here")
+		.with_secondary_in(SourceId::Virtual("units.lacon".into()), Position::new(3, 1, 30), None, "`kJ` declared here")
+		.with_note("rates are compared by dimension")
+	}
+
+	#[test]
+	fn code_frame() {
+		let error = Error::at(ErrorKind::Lexical(LexicalError::UnterminatedString), Position::new(2, 9, 18));
+		let frame = render_error(&error, &frame_source(), &RenderOptions::PLAIN);
+		assert_eq!(frame, "error[L0003]: Lexical error: Unterminated string literal\n --> frame.lacon:2:9\n  |\n2 |     name = \"abc\n  |            ^\n");
+	}
+
+	#[test]
+	fn wide_characters_take_two_columns() {
+		let error = Error::span(ErrorKind::Lexical(LexicalError::InvalidToken), Position::new(3, 8, 34), Position::new(3, 9, 35));
+		assert!(render_error(&error, &frame_source(), &RenderOptions::PLAIN).ends_with("3 | 速度 = 5 x\n  |          ^\n"));
+		assert_eq!(SourceMap::new(&frame_source().source).line_of(31), 3);
+	}
+
+	#[test]
+	fn spans_cover_several_lines() {
+		let error = Error::span(ErrorKind::Lexical(LexicalError::UnterminatedString), Position::new(2, 9, 18), Position::new(3, 3, 29));
+		let frame = render_error(&error, &frame_source(), &RenderOptions::COLORED);
+		assert!(frame.contains("\x1b[1;31m^^^^\x1b[0m") && frame.contains("3 |\x1b[0m 速度") && frame.matches("^^^^").count() == 2);
+	}
+
+	#[test]
+	fn scanner_errors_render() {
+		let text = "let a = 10 kgs\nlet b = 'abc\n";
+		let source = SourceFile::as_virtual("scan.lacon", text);
+		let ctx = UnitContext::new();
+		let (mut errors, mut typos) = (ErrorStorage::new(), ErrorStorage::new());
+		let mut scanner = Scanner::new(text.as_bytes(), &ctx, &mut errors, None);
+		check_unit_suffixes(scanner.scan_tokens(), &ctx, &mut typos);
+
+		let rendered: Vec<String> = errors.all().iter().chain(typos.all()).map(|stored| render_error(&stored.error, &source, &RenderOptions::PLAIN)).collect();
+		assert_eq!(rendered.len(), 2);
+		assert!(rendered[0].ends_with("2 | let b = 'abc\n  |          ^^^\n"), "{}", rendered[0]);
+		assert!(rendered[1].contains("1 | let a = 10 kgs\n  |            ^^^\n"), "{}", rendered[1]);
+	}
+
+	#[test]
+	fn offsets_snap_to_characters() {
+		// Смещение посреди символа прижимается к его началу
+		let source = SourceFile::as_virtual("cjk.lacon", "速度 = 5\n");
+		let error = Error::span(ErrorKind::Lexical(LexicalError::InvalidToken), Position::new(1, 1, 1), Position::new(1, 2, 4));
+		assert!(render_error(&error, &source, &RenderOptions::PLAIN).ends_with("1 | 速度 = 5\n  | ^^\n"));
+	}
+
+	#[test]
+	fn labelled_spans() {
+		assert_eq!(
+			render_error(&rate_mismatch(), &rate_source(), &RenderOptions::PLAIN),
+			"error[U0013]: Semantic error: Rate `kJ/s` does not match `<Mass> ÷ delta <Time>`\n --> rates.lacon:2:15\n  |\n\
+			 2 | consumption = 5kJ / delta 1s\n  |               ^^^ expected a mass rate\n\
+			 1 | consumption<Expr>: <Mass> ÷ delta <Time>\n  |                    ------ declared here\n\
+			 \x20::: units.lacon:3:1: `kJ` declared here\n  = note: rates are compared by dimension\n"
+		);
+	}
+
+	#[test]
+	fn color_is_chosen_by_the_caller() {
+		let plain = framed(&rate_mismatch(), Severity::Error, &rate_source(), &RenderOptions::PLAIN);
+		assert!(plain.console.is_none() && !plain.plain.contains('\x1b'));
+
+		let colored = framed(&rate_mismatch(), Severity::Error, &rate_source(), &RenderOptions::COLORED);
+		assert!(!colored.plain.contains('\x1b') && colored.console_text().starts_with("\x1b[1;31merror"));
+
+		let vars = |no_color: &'static str| move |name: &str| (name == "NO_COLOR").then(|| no_color.to_string());
+		assert!(RenderOptions::color_from_vars(vars(""), true));
+		assert!(!RenderOptions::color_from_vars(vars("1"), true));
+		assert!(!RenderOptions::color_from_vars(|_| None, false));
+	}
+
+	#[test]
+	fn suggestions_follow_the_reporter_locale() {
+		let source = SourceFile::as_virtual("typo.lacon", "let a = 10kgs\n");
+		let error = Error::span(ErrorKind::Semantic(SemanticError::Unit(UnitError::UnknownUnit("kgs".into()))), Position::new(1, 11, 10), Position::new(1, 14, 13)).did_you_mean("kg");
+		let path = std::env::temp_dir().join(format!("lacon-{}-typo.log", std::process::id()));
+
+		let reporter = ErrorReporter::Localized(Locale::Ru, Box::new(ErrorReporter::File(path.clone(), ErrorReporterFlag::Truncate)));
+		reporter.report_in(&error, &source, false).unwrap();
+		let text = std::fs::read_to_string(&path).unwrap();
+		std::fs::remove_file(path).unwrap();
+
+		assert!(text.contains("= help: возможно, имелось в виду `kg`?") && !text.contains("did you mean"));
+		assert_eq!(error.suggestions[0].message, "did you mean `kg`?");
+	}
+
+	#[test]
+	fn labels_reach_records() {
+		let record = DiagnosticRecord::new(&rate_mismatch(), Severity::Error, Some(&rate_source()));
+		let uris: Vec<_> = record.related.iter().map(|related| related.uri.as_deref()).collect();
+		assert_eq!(uris, [Some("rates.lacon"), Some("units.lacon")]);
+
+		let sarif = sarif_log(std::slice::from_ref(&record));
+		let result = &sarif["runs"][0]["results"][0];
+		assert_eq!(result["locations"][0]["message"]["text"], "expected a mass rate");
+		assert_eq!(result["relatedLocations"][1]["physicalLocation"]["artifactLocation"]["uri"], "units.lacon");
+
+		let lsp = lsp_diagnostic(&record);
+		assert_eq!(lsp["range"]["start"], serde_json::json!({ "line": 1, "character": 14 }));
+		assert_eq!(lsp["relatedInformation"][0]["message"], "declared here");
+		assert!(lsp["message"].as_str().is_some_and(|message| message.ends_with("expected a mass rate\nnote: rates are compared by dimension")));
+	}
+}
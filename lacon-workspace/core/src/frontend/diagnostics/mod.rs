@@ -0,0 +1,7 @@
+mod error_reporter;
+mod source_map;
+mod warning;
+
+pub use error_reporter::*;
+pub use source_map::*;
+pub use warning::*;
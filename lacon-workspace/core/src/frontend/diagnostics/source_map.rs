@@ -0,0 +1,71 @@
+/// Начала строк исходника: номер строки по смещению и текст строки по номеру (строки с 1, как в `Position`)
+#[derive(Debug, Clone)]
+pub struct SourceMap<'s> {
+	source: &'s str,
+	line_starts: Vec<usize>,
+}
+
+impl<'s> SourceMap<'s> {
+	pub fn new(source: &'s str) -> Self {
+		let line_starts = std::iter::once(0).chain(memchr::memchr_iter(b'\n', source.as_bytes()).map(|index| index + 1)).collect();
+		Self { source, line_starts }
+	}
+
+	pub fn line_count(&self) -> usize {
+		self.line_starts.len()
+	}
+
+	/// Строка, в которой лежит байт `offset`; смещения за концом файла попадают в последнюю строку
+	pub fn line_of(&self, offset: usize) -> usize {
+		self.line_starts.partition_point(|start| *start <= offset)
+	}
+
+	pub fn line_start(&self, line: usize) -> Option<usize> {
+		self.line_starts.get(line.checked_sub(1)?).copied()
+	}
+
+	/// Текст строки без `\n` и `\r`
+	pub fn line(&self, line: usize) -> Option<&'s str> {
+		let start = self.line_start(line)?;
+		let end = self.line_start(line + 1).map_or(self.source.len(), |next| next - 1);
+		Some(self.source.get(start..end)?.trim_end_matches('\r'))
+	}
+}
+
+/// Ширина символа в терминале: 2 у CJK и полноширинных, 0 у комбинируемых и невидимых
+pub fn char_width(c: char) -> usize {
+	match u32::from(c) {
+		0 | 0x0300..=0x036F | 0x0483..=0x0489 | 0x0591..=0x05BD | 0x0610..=0x061A | 0x064B..=0x065F | 0x0E31 | 0x0E34..=0x0E3A | 0x0E47..=0x0E4E => 0,
+		0x1AB0..=0x1AFF | 0x1DC0..=0x1DFF | 0x200B..=0x200F | 0x2028..=0x202E | 0x2060..=0x2064 | 0x20D0..=0x20FF | 0x3099 | 0x309A => 0,
+		0xFE00..=0xFE0F | 0xFE20..=0xFE2F | 0xFEFF | 0xE0100..=0xE01EF => 0,
+		0x1100..=0x115F | 0x231A | 0x231B | 0x2329 | 0x232A | 0x2E80..=0x303E | 0x3041..=0x33FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xA000..=0xA4CF => 2,
+		0xA960..=0xA97F | 0xAC00..=0xD7A3 | 0xF900..=0xFAFF | 0xFE10..=0xFE19 | 0xFE30..=0xFE6F | 0xFF00..=0xFF60 | 0xFFE0..=0xFFE6 => 2,
+		0x1F300..=0x1F64F | 0x1F900..=0x1F9FF | 0x20000..=0x2FFFD | 0x30000..=0x3FFFD => 2,
+		_ => 1,
+	}
+}
+
+/// Ширина префикса строки длиной `byte` байт с раскрытием табуляции до ближайшей позиции, кратной `tab_width`
+pub fn display_width(line: &str, byte: usize, tab_width: usize) -> usize {
+	line.char_indices().take_while(|(index, _)| *index < byte).fold(0, |width, (_, c)| match c {
+		'\t' => width + tab_width - width % tab_width.max(1),
+		c => width + char_width(c),
+	})
+}
+
+/// Строка для вывода: табуляция раскрыта пробелами так же, как в `display_width`
+pub fn expand_tabs(line: &str, tab_width: usize) -> String {
+	let mut expanded = String::with_capacity(line.len());
+	let mut width = 0;
+	for c in line.chars() {
+		if c == '\t' {
+			let spaces = tab_width - width % tab_width.max(1);
+			expanded.extend(std::iter::repeat_n(' ', spaces));
+			width += spaces;
+		} else {
+			expanded.push(c);
+			width += char_width(c);
+		}
+	}
+	expanded
+}
@@ -0,0 +1,304 @@
+use super::SourceMap;
+use crate::shared::{Error, ErrorFlag, ErrorKind, ErrorStorage, LintError, LintLevel, Position, Severity, StoredError, explain};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+const DIRECTIVE: &str = "lacon-allow-";
+
+/// Уровни диагностик по коду. Без настройки всё — `deny`, кроме собственных предупреждений линтера (`W...`)
+#[derive(Debug, Clone, Default)]
+pub struct LintRegistry {
+	levels: HashMap<&'static str, LintLevel>,
+}
+
+impl LintRegistry {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn set(&mut self, code: &str, level: LintLevel) -> Result<(), LintError> {
+		let explanation = explain(code).ok_or_else(|| LintError::UnknownCode(code.to_string()))?;
+		self.levels.insert(explanation.code, level);
+		Ok(())
+	}
+
+	pub fn level(&self, code: &str) -> LintLevel {
+		match self.levels.get(code) {
+			Some(level) => *level,
+			None if code.starts_with('W') => LintLevel::Warn,
+			None => LintLevel::Deny,
+		}
+	}
+
+	/// Секция `[lints]` из конфигурации проекта, остальные секции пропускаются:
+	///
+	/// ```text
+	/// [lints]
+	/// U0009 = "warn"   # юниты вне профиля не ломают сборку
+	/// W0001 = "allow"
+	/// ```
+	pub fn from_config(config: &str) -> (Self, Vec<Error>) {
+		let mut registry = Self::new();
+		let mut errors = Vec::new();
+		let mut in_lints = false;
+		let mut offset = 0;
+
+		for (index, raw) in config.split_inclusive('\n').enumerate() {
+			let position = Position::new(index + 1, 1, offset);
+			offset += raw.len();
+
+			let line = raw.split('#').next().unwrap_or_default().trim();
+			if line.starts_with('[') {
+				in_lints = line == "[lints]";
+				continue;
+			}
+			if !in_lints || line.is_empty() {
+				continue;
+			}
+
+			let Some((code, level)) = line.split_once('=') else {
+				errors.push(Error::at(ErrorKind::Lint(LintError::InvalidLevel(line.to_string())), position));
+				continue;
+			};
+			let level = level.trim().trim_matches('"');
+			let result = LintLevel::from_str(level).map_err(|_| LintError::InvalidLevel(level.to_string())).and_then(|level| registry.set(code.trim(), level));
+			if let Err(error) = result {
+				errors.push(Error::at(ErrorKind::Lint(error), position));
+			}
+		}
+
+		(registry, errors)
+	}
+
+	/// Важность для незаглушённой диагностики; `None` — `allow`
+	pub fn severity(&self, code: &str) -> Option<Severity> {
+		match self.level(code) {
+			LintLevel::Allow => None,
+			LintLevel::Warn => Some(Severity::Warning),
+			LintLevel::Deny => Some(Severity::Error),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuppressionScope {
+	/// Включительно, строки с 1
+	Lines(usize, usize),
+	File,
+}
+
+#[derive(Debug, Clone)]
+pub struct Suppression {
+	pub code: &'static str,
+	pub scope: SuppressionScope,
+	/// Начало директивы — на неё указывает предупреждение о ненужном подавлении
+	pub position: Position,
+}
+
+impl Suppression {
+	pub fn covers(&self, code: &str, line: Option<usize>) -> bool {
+		self.code == code
+			&& match (self.scope, line) {
+				(SuppressionScope::File, _) => true,
+				(SuppressionScope::Lines(first, last), Some(line)) => (first..=last).contains(&line),
+				(SuppressionScope::Lines(..), None) => false,
+			}
+	}
+}
+
+/// Директивы подавления в комментариях исходника:
+///
+/// ```text
+/// /* lacon-allow-next-line U0009 */          // только следующая строка
+/// /* lacon-allow-begin U0009, U0013 */       // до lacon-allow-end
+/// /* lacon-allow-end */
+/// /* lacon-allow-file U0007 */               // весь файл
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Suppressions {
+	pub entries: Vec<Suppression>,
+	/// Неизвестные коды и битые директивы
+	pub errors: Vec<Error>,
+}
+
+impl Suppressions {
+	// Комментарии не доходят до токенов, поэтому директивы ищутся прямо в тексте
+	pub fn parse(source: &str) -> Self {
+		let map = SourceMap::new(source);
+		let mut suppressions = Self::default();
+		let mut open: Vec<(Vec<&'static str>, Position)> = Vec::new();
+
+		for line in 1..=map.line_count() {
+			let (Some(content), Some(line_start)) = (map.line(line), map.line_start(line)) else {
+				continue;
+			};
+
+			for (index, _) in content.match_indices(DIRECTIVE) {
+				let before = &content[..index];
+				if !before.contains("/*") && !before.contains("/|\\") {
+					continue;
+				}
+
+				let position = Position::new(line, before.chars().count() + 1, line_start + index);
+				let rest = &content[index + DIRECTIVE.len()..];
+				let rest = rest.split("*/").next().unwrap_or_default();
+				let (name, codes) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+
+				let scope = match name {
+					"next-line" => SuppressionScope::Lines(line + 1, line + 1),
+					"file" => SuppressionScope::File,
+					"begin" => SuppressionScope::Lines(line, line),
+					"end" => {
+						match open.pop() {
+							Some((codes, begin)) => suppressions.push_all(codes, SuppressionScope::Lines(begin.line, line), begin),
+							None => suppressions.invalid("`lacon-allow-end` without `lacon-allow-begin`", position),
+						}
+						continue;
+					}
+					_ => {
+						suppressions.invalid("expected `next-line`, `begin`, `end` or `file`", position);
+						continue;
+					}
+				};
+
+				let codes = suppressions.codes(codes, position);
+				if codes.is_empty() {
+					suppressions.invalid("expected at least one diagnostic code", position);
+				} else if name == "begin" {
+					open.push((codes, position));
+				} else {
+					suppressions.push_all(codes, scope, position);
+				}
+			}
+		}
+
+		for (_, position) in open {
+			suppressions.invalid("`lacon-allow-begin` is never closed by `lacon-allow-end`", position);
+		}
+
+		suppressions
+	}
+
+	fn codes(&mut self, list: &str, position: Position) -> Vec<&'static str> {
+		let mut codes = Vec::new();
+		for code in list.split([',', ' ', '\t']).filter(|code| !code.is_empty()) {
+			match explain(code) {
+				Some(explanation) => codes.push(explanation.code),
+				None => self.errors.push(Error::at(ErrorKind::Lint(LintError::UnknownCode(code.to_string())), position)),
+			}
+		}
+		codes
+	}
+
+	fn push_all(&mut self, codes: Vec<&'static str>, scope: SuppressionScope, position: Position) {
+		self.entries.extend(codes.into_iter().map(|code| Suppression { code, scope, position }));
+	}
+
+	fn invalid(&mut self, reason: &'static str, position: Position) {
+		self.errors.push(Error::at(ErrorKind::Lint(LintError::InvalidDirective(reason)), position));
+	}
+}
+
+/// Применяет уровни и подавления к диагностикам файла.
+/// `Critical` остаются ошибками всегда; ненужные подавления и битые директивы добавляются как `W...`
+pub fn apply_lints(errors: &ErrorStorage, source: &str, registry: &LintRegistry) -> ErrorStorage {
+	let suppressions = Suppressions::parse(source);
+	let mut used = vec![false; suppressions.entries.len()];
+	let mut result = ErrorStorage::new();
+
+	for StoredError { error, flag, .. } in errors.all() {
+		if *flag == ErrorFlag::Critical {
+			result.add_with_severity(error.clone(), ErrorFlag::Critical, Severity::Error);
+			continue;
+		}
+
+		let code = error.kind.code();
+		let line = error.pos.as_ref().map(|pos| pos.start.line);
+		let mut suppressed = false;
+		for (index, suppression) in suppressions.entries.iter().enumerate() {
+			if suppression.covers(code, line) {
+				used[index] = true;
+				suppressed = true;
+			}
+		}
+
+		if !suppressed && let Some(severity) = registry.severity(code) {
+			result.add_with_severity(error.clone(), ErrorFlag::Common, severity);
+		}
+	}
+
+	let unused = suppressions
+		.entries
+		.iter()
+		.zip(used)
+		.filter(|(_, used)| !used)
+		.map(|(suppression, _)| Error::at(ErrorKind::Lint(LintError::UnusedSuppression(suppression.code.to_string())), suppression.position));
+	for error in suppressions.errors.iter().cloned().chain(unused) {
+		if let Some(severity) = registry.severity(error.kind.code()) {
+			result.add_with_severity(error, ErrorFlag::Common, severity);
+		}
+	}
+
+	result
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::frontend::diagnostics::{RenderOptions, render_diagnostic};
+	use crate::shared::{LexicalError, SemanticError, SourceFile, UnitError};
+
+	const CONFIG: &str = "[package]\nname = \"plant\"\n\n[lints]\nU0009 = \"warn\" # US units allowed for now\nU0007 = \"allow\"\nU9999 = \"deny\"\nU0006 = \"loud\"\n";
+	const SOURCE: &str = "/* lacon-allow-next-line U0006 */\na = 5m + 2s\nb = 5m + 2s\n/* lacon-allow-begin U0003, U0006 */\nc = 3 tick\n/* lacon-allow-end */\nd = 5ft\ne = 2em\n/* lacon-allow-next-line U0013 */\n";
+
+	fn linted() -> ErrorStorage {
+		let unit = |error| ErrorKind::Semantic(SemanticError::Unit(error));
+		let mut errors = ErrorStorage::new();
+		errors.add(Error::at(unit(UnitError::IncompatibleUnits("m".into(), "s".into())), Position::new(2, 5, 38)), ErrorFlag::Common);
+		errors.add(Error::at(unit(UnitError::IncompatibleUnits("m".into(), "s".into())), Position::new(3, 5, 50)), ErrorFlag::Common);
+		errors.add(Error::at(unit(UnitError::UnknownUnit("tick".into())), Position::new(5, 5, 101)), ErrorFlag::Common);
+		errors.add(Error::at(unit(UnitError::OutsideProfile("ft".into(), "SI".into())), Position::new(7, 5, 131)), ErrorFlag::Common);
+		errors.add(Error::at(unit(UnitError::Deferred("em".into())), Position::new(8, 5, 139)), ErrorFlag::Common);
+		errors.add(Error::at(ErrorKind::Lexical(LexicalError::UnterminatedString), Position::new(8, 5, 139)), ErrorFlag::Critical);
+		apply_lints(&errors, SOURCE, &LintRegistry::from_config(CONFIG).0)
+	}
+
+	#[test]
+	fn levels_from_config() {
+		let (registry, config_errors) = LintRegistry::from_config(CONFIG);
+		assert_eq!(registry.level("U0009"), LintLevel::Warn);
+		assert_eq!(registry.level("U0006"), LintLevel::Deny);
+		assert!(matches!(config_errors[0].kind, ErrorKind::Lint(LintError::UnknownCode(ref code)) if code == "U9999"));
+		assert!(matches!(config_errors[1].kind, ErrorKind::Lint(LintError::InvalidLevel(_))));
+	}
+
+	#[test]
+	fn suppressions_and_levels_apply() {
+		let linted = linted();
+		let found: Vec<_> = linted.all().iter().map(|stored| (stored.error.kind.code(), stored.severity, stored.error.pos.as_ref().map(|pos| pos.start.line))).collect();
+		assert_eq!(
+			found,
+			[
+				("U0006", Severity::Error, Some(3)),
+				("U0009", Severity::Warning, Some(7)),
+				("L0003", Severity::Error, Some(8)),
+				("W0001", Severity::Warning, Some(4)),
+				("W0001", Severity::Warning, Some(9)),
+			]
+		);
+		assert_eq!(linted.at_least(Severity::Error).count(), 2);
+	}
+
+	#[test]
+	fn warnings_render_as_warnings() {
+		let linted = linted();
+		let frame = render_diagnostic(&linted.all()[1].error, Severity::Warning, &SourceFile::as_virtual("lint.lacon", SOURCE), &RenderOptions::PLAIN);
+		assert!(frame.starts_with("warning[U0009]"));
+	}
+
+	#[test]
+	fn unbalanced_directives() {
+		let broken = Suppressions::parse("/* lacon-allow-begin U0009 */\n/* lacon-allow-file */\n");
+		assert_eq!(broken.errors.len(), 2);
+	}
+}
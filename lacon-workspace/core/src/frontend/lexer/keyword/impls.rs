@@ -1,4 +1,5 @@
-use crate::shared::SourceCodeReadModes;
+use crate::shared::characters::DELTA_SIGN;
+use crate::shared::{SourceCodeReadModes, closest};
 
 use super::KeywordKind;
 
@@ -139,7 +140,7 @@ impl KeywordKind {
 
 			// --- Константы и Маркеры ---
 			b"infinity" | b"Infinity" => Some(Self::NumberInfinity),
-			b"delta" => Some(Self::Delta),
+			b"delta" | DELTA_SIGN => Some(Self::Delta),
 			b"xor" => Some(Self::Xor),
 			b"bitwise" => Some(Self::Bitwise),
 			b"section" => Some(Self::SectionMaker),
@@ -150,6 +151,142 @@ impl KeywordKind {
 		}
 	}
 
+	/// Все написания ключевых слов, как их принимает `from_bytes`; кандидаты для «did you mean»
+	pub const SPELLINGS: &'static [&'static str] = &[
+		"if",
+		"else",
+		"elif",
+		"match",
+		"case",
+		"default",
+		"switch",
+		"for",
+		"while",
+		"until",
+		"spread",
+		"generate",
+		"combine",
+		"enumerate",
+		"filter",
+		"flatten",
+		"repeat",
+		"transform",
+		"transpose",
+		"loop",
+		"break",
+		"continue",
+		"return",
+		"yield",
+		"exit",
+		"cancel",
+		"defer",
+		"try",
+		"catch",
+		"finally",
+		"throw",
+		"async",
+		"await",
+		"coroutine",
+		"declare",
+		"class",
+		"interface",
+		"enum",
+		"cont",
+		"container",
+		"func",
+		"function",
+		"proc",
+		"procedure",
+		"event",
+		"let",
+		"var",
+		"variable",
+		"const",
+		"constant",
+		"entry",
+		"struct",
+		"structure",
+		"import",
+		"export",
+		"from",
+		"include",
+		"provide",
+		"new",
+		"use",
+		"schema",
+		"sanction",
+		"be",
+		"only",
+		"context",
+		"condition",
+		"action",
+		"capability",
+		"may",
+		"set",
+		"get",
+		"trigger",
+		"on",
+		"unset",
+		"untrigger",
+		"unsetall",
+		"type",
+		"alias",
+		"as",
+		"is",
+		"extends",
+		"implements",
+		"in",
+		"of",
+		"where",
+		"when",
+		"contains",
+		"with",
+		"true",
+		"false",
+		"negate",
+		"auto",
+		"nil",
+		"none",
+		"undefined",
+		"this",
+		"self",
+		"super",
+		"root",
+		"parent",
+		"origin",
+		"here",
+		"public",
+		"private",
+		"protected",
+		"internal",
+		"external",
+		"global",
+		"local",
+		"static",
+		"virtual",
+		"abstract",
+		"override",
+		"final",
+		"meta",
+		"reflect",
+		"attribute",
+		"and",
+		"or",
+		"not",
+		"infinity",
+		"Infinity",
+		"delta",
+		"xor",
+		"bitwise",
+		"section",
+		"Marker",
+	];
+
+	/// Ближайшее ключевое слово к опечатке: `fucntion` -> `function`
+	pub fn suggest(word: &str) -> Option<&'static str> {
+		closest(word, Self::SPELLINGS.iter().copied())
+	}
+
 	pub fn in_allowed(&self, code_read_mode: &SourceCodeReadModes) -> bool {
 		use KeywordKind::*;
 		use SourceCodeReadModes::*;
@@ -3,9 +3,13 @@ mod operator;
 mod scanner;
 mod syntax;
 mod token;
+mod typos;
+mod unit_declarations;
 
 pub use keyword::*;
 pub use operator::*;
 pub use scanner::*;
 pub use syntax::*;
 pub use token::*;
+pub use typos::*;
+pub use unit_declarations::*;
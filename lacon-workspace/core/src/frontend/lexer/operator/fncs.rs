@@ -13,9 +13,10 @@ pub fn match_operator(tail: &[u8]) -> OpMatch {
 	use OperatorKind::*;
 	use TokenKind::{BlockComment, LineComment, Operator, Underscore};
 
-	let c1 = &tail[0..1];
-	let c2 = if tail.len() > 1 { Some(&tail[1..2]) } else { None };
-	let c3 = if tail.len() > 2 { Some(&tail[2..3]) } else { None };
+	// Символы целиком, а не байты: `±`, `×`, `÷` занимают по два байта
+	let c1 = &tail[..char_len(tail[0]).min(tail.len())];
+	let c2 = next_char(tail, c1.len());
+	let c3 = c2.and_then(|c2| next_char(tail, c1.len() + c2.len()));
 	let (t_kind, consumed) = match c1 {
 		b"+" => match c2 {
 			Some(b"+") => (Operator(PlusPlus), 1),
@@ -186,5 +187,25 @@ pub fn match_operator(tail: &[u8]) -> OpMatch {
 		}
 	};
 
-	OpMatch { token_kind: t_kind, consume_count: consumed }
+	// consume_count — в байтах после первого: сканер уже съел один байт
+	let chars = [Some(c1), c2, c3];
+	let consume_count = chars[..=consumed].iter().flatten().map(|c| c.len()).sum::<usize>() - 1;
+
+	OpMatch { token_kind: t_kind, consume_count }
+}
+
+#[inline(always)]
+fn char_len(first: u8) -> usize {
+	match first {
+		0xF0.. => 4,
+		0xE0.. => 3,
+		0xC0.. => 2,
+		_ => 1,
+	}
+}
+
+#[inline(always)]
+fn next_char(tail: &[u8], offset: usize) -> Option<&[u8]> {
+	let first = *tail.get(offset)?;
+	tail.get(offset..offset + char_len(first))
 }
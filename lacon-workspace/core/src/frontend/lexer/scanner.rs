@@ -1,5 +1,8 @@
-use super::{KeywordKind, SyntaxKind, Token, TokenKind, match_operator};
-use crate::shared::{SourceCodeReadModes, Error, ErrorFlag, ErrorKind, ErrorStorage, LexicalError, Position, UnitContext, UnitKind};
+use super::{KeywordKind, SyntaxKind, Token, TokenKind, collect_unit_declarations, match_operator, unit_import};
+use crate::shared::characters::DELTA_SIGN;
+use crate::shared::{SourceCodeReadModes, Error, ErrorFlag, ErrorKind, ErrorStorage, LexicalError, Position, SemanticError, UnitContext, UnitDeclaration, UnitError, UnitLibrary, UnitRef};
+use std::borrow::Cow;
+use std::collections::HashMap;
 
 const ASCII_START: u128 = 0x7fffffe07fffffe0000000000000000;
 const ASCII_CONTINUE: u128 = 0x7fffffe87fffffe03ff000000000000;
@@ -8,7 +11,12 @@ const EOF_CHAR: u8 = b'\0';
 #[derive(Debug)]
 pub struct Scanner<'src> {
 	source: &'src [u8],
-	context: &'src UnitContext<'src>,
+	base_context: &'src UnitContext,
+	// Копируется при первом `declare unit`/`import units`, до этого сканер работает с общим контекстом
+	context: Cow<'src, UnitContext>,
+	library: Option<&'src UnitLibrary>,
+	declarations: Vec<UnitDeclaration>,
+	imported: Vec<String>,
 	errors_storage: &'src mut ErrorStorage,
 	tokens: Vec<Token<'src>>,
 	code_mode: Option<SourceCodeReadModes>,
@@ -19,6 +27,10 @@ pub struct Scanner<'src> {
 	indent_stack: Vec<usize>,
 	context_stack: Vec<TokenKind>,
 	string_stack: Vec<(u8, bool)>,
+	// Уже разобранные юниты по написанию: повторный `kg` не разбирается и не аллоцирует заново
+	units: HashMap<&'src [u8], UnitRef>,
+	// Первый токен текущей строки: по нему видно `declare unit` и `import units`
+	line_start: usize,
 	is_at_line_start: bool,
 	had_whitespace: bool,
 	#[cfg(debug_assertions)]
@@ -39,6 +51,11 @@ impl<'src> Scanner<'src> {
 		self.indent_stack.push(0);
 		self.context_stack.clear();
 		self.string_stack.clear();
+		self.units.clear();
+		self.context = Cow::Borrowed(self.base_context);
+		self.declarations.clear();
+		self.imported.clear();
+		self.line_start = 0;
 		self.is_at_line_start = true;
 		self.had_whitespace = false;
 		#[cfg(debug_assertions)]
@@ -53,7 +70,11 @@ impl<'src> Scanner<'src> {
 		Self {
 			source,
 			code_mode,
-			context: ctx,
+			base_context: ctx,
+			context: Cow::Borrowed(ctx),
+			library: None,
+			declarations: Vec::new(),
+			imported: Vec::new(),
 			errors_storage,
 			tokens: Vec::new(),
 			start: 0,
@@ -63,6 +84,8 @@ impl<'src> Scanner<'src> {
 			indent_stack: vec![0],
 			context_stack: Vec::new(),
 			string_stack: Vec::new(),
+			units: HashMap::new(),
+			line_start: 0,
 			is_at_line_start: true,
 			had_whitespace: false,
 			#[cfg(debug_assertions)]
@@ -70,9 +93,30 @@ impl<'src> Scanner<'src> {
 		}
 	}
 
+	/// Объявления для `import units from "..."` берутся из `library`
+	pub fn with_library(mut self, library: &'src UnitLibrary) -> Self {
+		self.library = Some(library);
+		self
+	}
+
+	/// Контекст с юнитами, объявленными и импортированными в файле
+	pub fn context(&self) -> &UnitContext {
+		&self.context
+	}
+
+	pub fn into_context(self) -> UnitContext {
+		self.context.into_owned()
+	}
+
+	/// Собственные `declare unit` файла, без импортированных — их хост кладёт в `UnitLibrary`
+	pub fn declarations(&self) -> &[UnitDeclaration] {
+		&self.declarations
+	}
+
 	pub fn scan_tokens(&mut self) -> &Vec<Token<'_>> {
+		// SOF не занимает байтов: смещения токенов — настоящие байтовые смещения в исходнике
 		self.tokens.push(Token::bare(TokenKind::SOF, self.position));
-		self.position.offset += 1;
+		self.line_start = self.tokens.len();
 		while !self.is_at_end() {
 			if self.is_at_line_start {
 				self.handle_indentation();
@@ -83,6 +127,7 @@ impl<'src> Scanner<'src> {
 				self.scan_token();
 			}
 		}
+		self.register_line_units();
 		while self.indent_stack.len() > 1 {
 			self.indent_stack.pop();
 			let level = (self.indent_stack.len() - 1) as u8;
@@ -92,6 +137,16 @@ impl<'src> Scanner<'src> {
 		&self.tokens
 	}
 
+	// Юнит не должен обрывать слово: `5 минутами`, `5 metersX` — не юниты
+	#[inline(always)]
+	fn continues_word(rest: &[u8]) -> bool {
+		match rest.first() {
+			None => false,
+			Some(b) if b.is_ascii() => b.is_ascii_alphanumeric() || *b == b'_',
+			Some(_) => (2..=rest.len().min(4)).find_map(|len| std::str::from_utf8(&rest[..len]).ok()).and_then(|s| s.chars().next()).is_some_and(char::is_alphanumeric),
+		}
+	}
+
 	#[inline(always)]
 	fn has_byte(chunk: usize, byte: u8) -> bool {
 		let word = chunk ^ (usize::MAX / 255 * byte as usize);
@@ -140,6 +195,7 @@ impl<'src> Scanner<'src> {
 			}
 			b'\n' => {
 				self.add_token_raw(TokenKind::Newline);
+				self.register_line_units();
 				self.is_at_line_start = true;
 				self.had_whitespace = false;
 				self.start = self.current;
@@ -220,6 +276,12 @@ impl<'src> Scanner<'src> {
 			_ if self.is_identifier_start(c) => {
 				self.scan_identifier();
 			}
+			// Греческий блок идентификатором не считается, но `Δ` — это `delta`
+			_ if self.source[self.current - 1..].starts_with(DELTA_SIGN) => {
+				self.advance();
+				let kind = self.get_keyword(DELTA_SIGN);
+				self.add_token(kind);
+			}
 
 			_ => self.handle_operator(c),
 		}
@@ -406,6 +468,15 @@ impl<'src> Scanner<'src> {
 			self.consume_digits_with_underscore(10);
 		}
 
+		// Порядок входит в число: `1e-10`, `6.62607015e-34J`, `2E5`
+		if radix == 10 && matches!(self.first(), b'e' | b'E') && (self.second().is_ascii_digit() || (matches!(self.second(), b'+' | b'-') && self.third().is_ascii_digit())) {
+			self.advance();
+			if matches!(self.first(), b'+' | b'-') {
+				self.advance();
+			}
+			self.consume_digits_with_underscore(10);
+		}
+
 		let lexeme = &self.source[self.start..self.current];
 		self.process_unit_suffix(lexeme);
 	}
@@ -452,30 +523,26 @@ impl<'src> Scanner<'src> {
 		}
 
 		let unit_input = &lookahead[ws_len..];
-		let unit_match_len = self.context.tree.longest_match(unit_input);
 
-		if unit_match_len > 0 {
-			let nc = unit_input.get(unit_match_len).copied().unwrap_or(EOF_CHAR);
-			if !(nc.is_ascii_alphanumeric() || nc == b'_') {
-				self.add_token(TokenKind::Number);
-
-				self.current += ws_len;
-				self.position.offset += ws_len;
-				self.position.column += ws_len;
+		if let Some((unit_match_len, unit)) = self.resolve_unit(unit_input)
+			&& !Self::continues_word(&unit_input[unit_match_len..])
+		{
+			self.add_token(TokenKind::Number);
 
-				self.start = self.current;
-				self.start_position = self.position;
+			self.current += ws_len;
+			self.position.offset += ws_len;
+			self.position.column += ws_len;
 
-				let unit_lexeme = &unit_input[..unit_match_len];
-				let unit_kind = self.context.lookup.get(unit_lexeme).cloned().unwrap_or(UnitKind::None);
+			self.start = self.current;
+			self.start_position = self.position;
 
-				self.current += unit_match_len;
-				self.position.offset += unit_match_len;
-				self.position.column += unit_match_len;
+			// Колонка считается в символах: `минут` — пять колонок, а не десять байт
+			self.current += unit_match_len;
+			self.position.offset += unit_match_len;
+			self.position.column += unit_input[..unit_match_len].iter().filter(|&&b| (b & 0xC0) != 0x80).count();
 
-				self.add_token(TokenKind::Unit(unit_kind));
-				return;
-			}
+			self.add_token(TokenKind::Unit(unit));
+			return;
 		}
 
 		if lexeme == b"n" {
@@ -493,6 +560,70 @@ impl<'src> Scanner<'src> {
 		}
 	}
 
+	// `declare unit` и `import units` действуют со следующей строки того же файла
+	fn register_line_units(&mut self) {
+		let start = std::mem::replace(&mut self.line_start, self.tokens.len());
+		let line = &self.tokens[start..];
+		let first = line.iter().find(|t| !matches!(t.kind, TokenKind::Indent(_) | TokenKind::Dedent(_))).and_then(|t| t.kind.keyword());
+
+		let (own, declarations) = match first {
+			Some(KeywordKind::Declare) => (true, collect_unit_declarations(line)),
+			Some(KeywordKind::Import) => match unit_import(line) {
+				Some((position, Ok(path))) => (false, self.imported_declarations(position, path)),
+				Some((position, Err(error))) => (false, vec![(position, Err(error))]),
+				None => return,
+			},
+			_ => return,
+		};
+
+		for (position, declaration) in declarations {
+			match declaration.and_then(|declaration| self.context.to_mut().declare(declaration.clone()).map(|_| declaration)) {
+				Ok(declaration) => {
+					// Новый символ может поменять разбор уже встреченных написаний
+					self.units.clear();
+					if own {
+						self.declarations.push(declaration);
+					}
+				}
+				Err(error) => {
+					self.errors_storage.add(Error::at(ErrorKind::Semantic(SemanticError::Unit(error)), position), ErrorFlag::Common);
+				}
+			}
+		}
+	}
+
+	// Повторный импорт того же файла ничего не делает
+	fn imported_declarations(&mut self, position: Position, path: String) -> Vec<(Position, Result<UnitDeclaration, UnitError>)> {
+		if self.imported.contains(&path) {
+			return Vec::new();
+		}
+
+		match self.library.and_then(|library| library.get(&path)) {
+			Some(declarations) => {
+				self.imported.push(path);
+				declarations.iter().map(|declaration| (position, Ok(declaration.clone()))).collect()
+			}
+			None => vec![(position, Err(UnitError::UnknownUnitImport(path)))],
+		}
+	}
+
+	// Юнит-выражение не содержит пробелов и не продолжается через `,;)]}`, поэтому слово до такого байта,
+	// однажды разобранное целиком, дальше берётся из кэша
+	fn resolve_unit(&mut self, input: &'src [u8]) -> Option<(usize, UnitRef)> {
+		let word_len = input.iter().position(|b| b.is_ascii_whitespace() || b",;)]}".contains(b)).unwrap_or(input.len());
+		let word = &input[..word_len];
+		if let Some(unit) = self.units.get(word) {
+			return Some((word_len, unit.clone()));
+		}
+
+		let (len, unit) = self.context.parse_unit(input)?;
+		let unit = UnitRef::from(unit);
+		if len == word_len {
+			self.units.insert(word, unit.clone());
+		}
+		Some((len, unit))
+	}
+
 	fn scan_string(&mut self, quote: u8) {
 		let is_multiline = quote == b'"' && self.first() == b'"' && self.second() == b'"';
 		if is_multiline {
@@ -617,11 +748,9 @@ impl<'src> Scanner<'src> {
 		let tail = &self.source[self.current - 1..];
 		let operator = match_operator(tail);
 
-		let consume = operator.consume_count;
-		if consume > 0 {
-			self.current += consume;
-			self.position.offset += consume;
-			self.position.column += consume;
+		// Через advance: у многобайтовых операторов колонка растёт на символ, а не на байт
+		for _ in 0..operator.consume_count {
+			self.advance();
 		}
 
 		match operator.token_kind {
@@ -685,3 +814,32 @@ impl<'src> Scanner<'src> {
 		}
 	}
 }
+
+/// Для тестов: токены `source` в чистом контексте и отдельное хранилище для диагностик проверки
+#[cfg(test)]
+pub fn scanned<R>(source: &[u8], check: impl FnOnce(&[Token], &UnitContext, &mut ErrorStorage) -> R) -> R {
+	let ctx = UnitContext::new();
+	let (mut scan_errors, mut errors) = (ErrorStorage::new(), ErrorStorage::new());
+	let mut scanner = Scanner::new(source, &ctx, &mut scan_errors, None);
+	check(scanner.scan_tokens(), &ctx, &mut errors)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn unit_columns_count_characters() {
+		let plus = scanned("x = 5 минут + 2\n".as_bytes(), |tokens, _, _| tokens.iter().find(|token| token.lexeme == Some(b"+")).expect("plus").position);
+		assert_eq!((plus.column, plus.offset), (13, 17));
+	}
+
+	#[test]
+	fn exponents_are_part_of_the_number() {
+		scanned("a = 1e-10 + 2E5\nh = 6.62607015e-34J⋅s\nw = 5em\n".as_bytes(), |tokens, ctx, _| {
+			let numbers: Vec<_> = tokens.iter().filter(|token| token.kind == TokenKind::Number).filter_map(|token| token.lexeme).collect();
+			assert_eq!(numbers, [&b"1e-10"[..], b"2E5", b"6.62607015e-34", b"5"]);
+			assert_eq!(tokens.iter().filter_map(|token| token.kind.unit()).map(|unit| unit.kind).collect::<Vec<_>>(), [ctx.unit("J⋅s").kind, ctx.unit("em").kind]);
+		});
+	}
+}
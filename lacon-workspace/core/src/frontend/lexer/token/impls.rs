@@ -1,7 +1,8 @@
 use super::super::KeywordKind;
 use super::{Token, TokenFlags, TokenKind};
-use crate::shared::{Position, UnitKind};
+use crate::shared::{Position, ResolvedUnit};
 use std::fmt;
+use std::ops::Deref;
 
 impl<'a> Token<'a> {
 	pub fn new(kind: TokenKind, is_at_line_start: bool, has_whitespace: bool, lexeme: Option<&'a [u8]>, position: Position) -> Self {
@@ -43,9 +44,9 @@ impl TokenKind {
 		}
 	}
 
-	pub fn unit(&self) -> Option<UnitKind> {
+	pub fn unit(&self) -> Option<&ResolvedUnit> {
 		match self {
-			TokenKind::Unit(s) => Some(*s),
+			TokenKind::Unit(s) => Some(s.deref()),
 			_ => None,
 		}
 	}
@@ -1,5 +1,5 @@
 use super::super::{KeywordKind, OperatorKind, SyntaxKind};
-use crate::shared::UnitKind;
+use crate::shared::UnitRef;
 
 #[repr(u8)]
 #[derive(Debug, Clone, PartialEq)]
@@ -55,5 +55,5 @@ pub enum TokenKind {
 
 	Expression, // \\ Выражение: Some / Some * Soma + Some...
 
-	Unit(UnitKind),
+	Unit(UnitRef), // общий на все вхождения юнита в файле
 }
@@ -0,0 +1,188 @@
+use super::{KeywordKind, OperatorKind, SyntaxKind, Token, TokenFlags, TokenKind};
+use crate::shared::{Error, ErrorFlag, ErrorKind, ErrorStorage, Position, SemanticError, SyntaxError, UnitContext, UnitError, closest};
+
+/// Слово сразу после числа, которое не разобралось как юнит: `10 kgs`, `5 Kg`, `3tick`.
+/// Слитное написание — всегда ошибка; через пробел — только если есть похожий юнит, иначе это может быть обычное имя.
+/// Не юниты: запись в другой системе счисления (`0xFF`), символы вроде `|0⟩` и размеры в байтах (`1.5GiB`)
+pub fn check_unit_suffixes(tokens: &[Token], ctx: &UnitContext, errors: &mut ErrorStorage) {
+	for pair in tokens.windows(2) {
+		let [number, suffix] = pair else {
+			continue;
+		};
+		if number.kind != TokenKind::Number || suffix.kind != TokenKind::Identifier || suffix.position.line != number.position.line {
+			continue;
+		}
+		let Some(symbol) = word(suffix) else {
+			continue;
+		};
+		if !symbol.starts_with(char::is_alphabetic) || (word(number) == Some("0") && is_radix(symbol)) || ctx.is_byte_size(symbol) {
+			continue;
+		}
+
+		let suggestion = ctx.suggest_unit(symbol);
+		if suggestion.is_none() && suffix.flags.contains(TokenFlags::HAS_PRECEDING_WHITESPACE) {
+			continue;
+		}
+
+		let error = Error::span(ErrorKind::Semantic(SemanticError::Unit(UnitError::UnknownUnit(symbol.to_string()))), suffix.position, end_after(suffix.position, symbol));
+		let error = match suggestion {
+			Some(unit) => error.did_you_mean(&unit),
+			None => error,
+		};
+		errors.add(error, ErrorFlag::Common);
+	}
+}
+
+/// Два идентификатора подряд в начале инструкции: `fucntion total(a, b)`. Первый почти наверняка опечатка в ключевом слове,
+/// если после имени стоит то, что это ключевое слово допускает: `(` у функции, `=` или `<` у переменной.
+/// Текст шаблонных строк не проверяется: многострочный шаблон сканер отдаёт обычными токенами
+pub fn check_keyword_typos(tokens: &[Token], errors: &mut ErrorStorage) {
+	let mut in_template = false;
+
+	for (index, first) in tokens.iter().enumerate() {
+		if first.kind == TokenKind::Syntax(SyntaxKind::GraveAccent) {
+			in_template = !in_template;
+			continue;
+		}
+		if in_template || !first.flags.contains(TokenFlags::AT_LINE_START) || first.kind != TokenKind::Identifier {
+			continue;
+		}
+		if !tokens.get(index + 1).is_some_and(|second| second.kind == TokenKind::Identifier && second.position.line == first.position.line) {
+			continue;
+		}
+		let Some(typo) = word(first) else {
+			continue;
+		};
+
+		let after_name = tokens.get(index + 2).map(|token| &token.kind);
+		let candidates = KeywordKind::SPELLINGS
+			.iter()
+			.copied()
+			.filter(|spelling| KeywordKind::from_bytes(spelling.as_bytes()).is_some_and(|keyword| names_followed_by(keyword, after_name)));
+		let Some(keyword) = closest(typo, candidates) else {
+			continue;
+		};
+
+		let error = Error::span(ErrorKind::Syntax(SyntaxError::UnknownKeyword(typo.to_string())), first.position, end_after(first.position, typo));
+		errors.add(error.did_you_mean(keyword), ErrorFlag::Common);
+	}
+}
+
+/// Ошибка для необъявленного имени с подсказкой из видимых в области имён
+pub fn undefined_name<'a>(name: &str, start: Position, in_scope: impl IntoIterator<Item = &'a str>) -> Error {
+	let error = Error::span(ErrorKind::Semantic(SemanticError::UndefinedName(name.to_string())), start, end_after(start, name));
+	match closest(name, in_scope) {
+		Some(candidate) => error.did_you_mean(candidate),
+		None => error,
+	}
+}
+
+fn word<'t>(token: &Token<'t>) -> Option<&'t str> {
+	token.lexeme.and_then(|lexeme| std::str::from_utf8(lexeme).ok())
+}
+
+fn end_after(start: Position, text: &str) -> Position {
+	Position::new(start.line, start.column + text.chars().count(), start.offset + text.len())
+}
+
+// Объявление с именем и то, что может идти сразу после имени
+fn names_followed_by(keyword: KeywordKind, after_name: Option<&TokenKind>) -> bool {
+	use KeywordKind::*;
+	use TokenKind::{Keyword, Newline, Operator, Syntax};
+
+	let Some(after_name) = after_name else {
+		return false;
+	};
+	match keyword {
+		Function | Procedure | Callable | Coroutine | Event => matches!(after_name, Syntax(SyntaxKind::LeftParenthesis)),
+		Variable | Constant => matches!(after_name, Operator(OperatorKind::Equal | OperatorKind::Less | OperatorKind::Colon) | Newline),
+		Class | Interface | Enum | Container | Structure | Schema | Type | Alias => {
+			matches!(
+				after_name,
+				Syntax(SyntaxKind::LeftBrace) | Operator(OperatorKind::Equal | OperatorKind::Less | OperatorKind::Colon) | Keyword(Extends | Implements) | Newline
+			)
+		}
+		_ => false,
+	}
+}
+
+// `x`/`o`/`b` после `0`: `0xFF`, `0b1010`
+fn is_radix(symbol: &str) -> bool {
+	let digits = symbol.strip_prefix(['x', 'X', 'o', 'O', 'b', 'B']);
+	digits.is_some_and(|digits| !digits.is_empty() && digits.chars().all(|c| c.is_ascii_hexdigit() || c == '_'))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::frontend::diagnostics::{RenderOptions, render_error};
+	use crate::frontend::lexer::scanned;
+	use crate::shared::{DiagnosticRecord, Severity, SourceFile, sarif_log};
+
+	fn flagged_lines(text: &[u8]) -> Vec<usize> {
+		scanned(text, |tokens, ctx, errors| {
+			check_unit_suffixes(tokens, ctx, errors);
+			check_keyword_typos(tokens, errors);
+			errors.all().iter().filter_map(|stored| Some(stored.error.pos.as_ref()?.start.line)).collect()
+		})
+	}
+
+	#[test]
+	fn closest_spellings() {
+		let ctx = UnitContext::new();
+		assert_eq!(ctx.suggest_unit("kgs").as_deref(), Some("kg"));
+		assert_eq!(ctx.suggest_unit("Kg").as_deref(), Some("kg"));
+		assert_eq!(KeywordKind::suggest("fucntion"), Some("function"));
+		assert_eq!(KeywordKind::suggest("total"), None);
+	}
+
+	#[test]
+	fn fix_its_replace_the_typo() {
+		let text = "let a = 10 kgs\nlet b = 5 Kg\nlet c = 2 apples\nfucntion total(a, b)\n";
+		let errors = scanned(text.as_bytes(), |tokens, ctx, errors| {
+			check_unit_suffixes(tokens, ctx, errors);
+			check_keyword_typos(tokens, errors);
+			errors.all().iter().map(|stored| stored.error.clone()).collect::<Vec<_>>()
+		});
+
+		let fixes: Vec<_> = errors.iter().filter_map(|error| error.suggestions[0].edit.as_ref()).map(|edit| (edit.start.offset, edit.end.offset, edit.replacement.as_str())).collect();
+		assert_eq!(fixes, [(11, 14, "kg"), (25, 27, "kg"), (45, 53, "function")]);
+
+		let sarif = sarif_log(&[DiagnosticRecord::new(&errors[2], Severity::Error, Some(&SourceFile::as_virtual("typo.lacon", text)))]);
+		assert_eq!(sarif["runs"][0]["results"][0]["fixes"][0]["artifactChanges"][0]["replacements"][0]["insertedContent"]["text"], "function");
+	}
+
+	#[test]
+	fn undefined_names_suggest_names_in_scope() {
+		let source = SourceFile::as_virtual("typo.lacon", "let total = prise * 2\n");
+		let error = undefined_name("prise", Position::new(1, 13, 12), ["price", "quantity"]);
+		assert!(render_error(&error, &source, &RenderOptions::PLAIN).ends_with("1 | let total = prise * 2\n  |             ^^^^^\n  = help: did you mean `price`?\n"));
+	}
+
+	#[test]
+	fn numbers_are_not_unit_typos() {
+		let text = "a = 1e-10\nb = 6.62607015e-34J⋅s\nc = 0xFF\nd = |0⟩ + |1⟩\ne = 1.5GiB + 20MB\nf = 10 kgs\n";
+		assert_eq!(flagged_lines(text.as_bytes()), [6]);
+	}
+
+	#[test]
+	fn keywords_need_their_grammar() {
+		let text = "fucntion total(a, b)\nconts limit = 5\nProcessed by: admin\nreport = `Total:\nProcessed by: ${user}`\n";
+		assert_eq!(flagged_lines(text.as_bytes()), [1, 2]);
+	}
+
+	#[test]
+	fn corpus_lines_are_clean() {
+		// Строки из tests/files/big.lacon, на которых проверки раньше срабатывали
+		let text = "\
+let ψ = |0⟩ + |1⟩ / sqrt(2.0)  // Hadamard |+⟩ state
+if abs(a - b) < 1e-10 { return None }
+let report = `Report
+Processed by: ${System::current_user()}@${System::hostname()}`
+let data_size = 1.5GiB → MB      // 1610.61 MB
+public const PLANCK_CONSTANT: f64 = 6.62607015e-34J⋅s
+let G = 6.67430e-11
+";
+		assert_eq!(flagged_lines(text.as_bytes()), Vec::<usize>::new());
+	}
+}
@@ -0,0 +1,637 @@
+use super::{KeywordKind, OperatorKind, Scanner, SyntaxKind, Token, TokenFlags, TokenKind};
+use crate::shared::{
+	Error, ErrorFlag, ErrorKind, ErrorStorage, FormatOptions, Position, PrefixGroup, RateSignature, Ratio, RelativeContext, ResolvedUnit, SemanticError, UNITS, Uncertain, UnitBase, UnitContext, UnitDeclaration, UnitError, UnitId, UnitKind, UnitProfile,
+};
+use std::iter::Peekable;
+use std::ops::Range;
+use std::str::FromStr;
+
+type Declared = (Position, Result<UnitDeclaration, UnitError>);
+/// Позиция оператора (`delta`, `±`), имя поля и найденное значение
+pub type RateEntry<T> = (Position, Option<String>, Result<T, UnitError>);
+/// Значение с погрешностью и юнитом; `None` — безразмерное число
+pub type Measured = (Uncertain, Option<ResolvedUnit>);
+
+/// Объявления юнитов в исходнике, по одному на строку:
+///
+/// ```text
+/// declare unit requisition: Requisition   // новая базовая величина
+/// declare unit cell: Length with SI       // существующая величина, разрешены SI-префиксы
+/// declare unit tick = 0.05 s              // относительно другого юнита
+/// declare unit degR = 0.5555555556 K      // можно и со смещением: `= 1 K + 273.15`
+/// ```
+///
+/// Юниты, объявленные выше, доступны в следующих строках: `declare unit shift = 480 tick`
+pub fn collect_unit_declarations(tokens: &[Token]) -> Vec<Declared> {
+	let mut declarations = Vec::new();
+	let mut iter = tokens.iter().peekable();
+
+	while let Some(token) = iter.next() {
+		if token.kind != TokenKind::Keyword(KeywordKind::Declare) {
+			continue;
+		}
+		if iter.next_if(|t| t.kind == TokenKind::Identifier && t.lexeme == Some(b"unit")).is_none() {
+			continue;
+		}
+
+		declarations.push((token.position, parse_declaration(&mut iter)));
+
+		// Остаток строки после ошибки пропускаем
+		while iter.next_if(|t| !is_line_end(t)).is_some() {}
+	}
+
+	declarations
+}
+
+/// Импорт объявлений другого файла: `import units from "./game.lacon"`. Остальные `import` сюда не относятся
+pub fn unit_import(tokens: &[Token]) -> Option<(Position, Result<String, UnitError>)> {
+	let mut iter = tokens.iter().skip_while(|t| matches!(t.kind, TokenKind::Indent(_) | TokenKind::Dedent(_)));
+	let import = iter.next().filter(|t| t.kind == TokenKind::Keyword(KeywordKind::Import))?;
+	iter.next().filter(|t| t.kind == TokenKind::Identifier && t.lexeme == Some(b"units"))?;
+
+	let mut rest = iter.filter(|t| !matches!(t.kind, TokenKind::Syntax(SyntaxKind::DoubleQuote | SyntaxKind::SingleQuote)));
+	let path = match (rest.next(), rest.next()) {
+		(Some(from), Some(path)) if from.kind == TokenKind::Keyword(KeywordKind::From) && path.kind == TokenKind::String => text(path).ok_or(UnitError::InvalidDeclaration("expected import path")),
+		_ => Err(UnitError::InvalidDeclaration("expected import path")),
+	};
+	Some((import.position, path))
+}
+
+/// Регистрирует `declare unit` из `source` в `ctx` — для хоста, которому нужен только контекст.
+/// При обычном сканировании объявления и так действуют сразу, см. `Scanner::into_context`
+pub fn declare_units(source: &[u8], ctx: &mut UnitContext, errors: &mut ErrorStorage) -> Vec<UnitId> {
+	let known = UNITS.len() + ctx.user_units.len();
+	let extended = {
+		let mut scanner = Scanner::new(source, ctx, errors, None);
+		scanner.scan_tokens();
+		scanner.into_context()
+	};
+	*ctx = extended;
+
+	(known..UNITS.len() + ctx.user_units.len()).map(|index| UnitId(index as u16)).collect()
+}
+
+/// Профиль юнитов файла: `declare units SI` (`metric`, `US`). Если объявлений несколько, действует последнее
+pub fn select_unit_profile(tokens: &[Token]) -> Option<(Position, Result<UnitProfile, UnitError>)> {
+	let mut selected = None;
+	let mut iter = tokens.iter().peekable();
+
+	while let Some(token) = iter.next() {
+		if token.kind != TokenKind::Keyword(KeywordKind::Declare) || iter.next_if(|t| t.kind == TokenKind::Identifier && t.lexeme == Some(b"units")).is_none() {
+			continue;
+		}
+
+		let profile = iter.next_if(|t| t.kind == TokenKind::Identifier).and_then(text).ok_or(UnitError::InvalidDeclaration("expected unit profile name"));
+		selected = Some((token.position, profile.and_then(|name| UnitProfile::by_name(&name).ok_or(UnitError::UnknownProfile(name)))));
+	}
+
+	selected
+}
+
+/// Диагностики для юнитов вне профиля, с предложенным переводом: `5ft` -> `1.524m`
+pub fn check_unit_profile(tokens: &[Token], ctx: &UnitContext, profile: &UnitProfile, errors: &mut ErrorStorage) {
+	for (index, token) in tokens.iter().enumerate() {
+		let Some(unit) = token.kind.unit() else {
+			continue;
+		};
+		let value = index.checked_sub(1).and_then(|prev| tokens.get(prev)).filter(|t| t.kind == TokenKind::Number).and_then(number).unwrap_or(1.0);
+
+		if let Err(error) = ctx.check_profile(value, unit, profile) {
+			errors.add(Error::at(ErrorKind::Semantic(SemanticError::Unit(error)), token.position), ErrorFlag::Common);
+		}
+	}
+}
+
+/// Сигнатуры скоростей в схеме: `consumption<Expr>?: <Mass> ÷ delta <Time>`.
+/// Разбирается вся сигнатура после `:`, так что `<Rate<Energy>>` — ошибка, а не пропуск
+pub fn collect_rate_signatures(tokens: &[Token]) -> Vec<RateEntry<RateSignature>> {
+	delta_positions(tokens)
+		.map(|index| {
+			let line = tokens[index].position.line;
+			let start = tokens[..index]
+				.iter()
+				.rposition(|t| t.position.line != line || matches!(t.kind, TokenKind::Operator(OperatorKind::Colon | OperatorKind::QuestionColon)))
+				.map_or(0, |colon| colon + 1);
+			let end = tokens[index..].iter().position(|t| is_line_end(t) || t.kind == TokenKind::Operator(OperatorKind::Equal)).map_or(tokens.len(), |offset| index + offset);
+			(tokens[index].position, field_name(tokens, index), source_text(&tokens[start..end]).parse())
+		})
+		.collect()
+}
+
+/// Скорости в исходнике, приведённые к когерентному знаменателю: `10kJ / delta 2s` -> 5 kJ/s, `(10kJ + 2kJ) / delta 2s` -> 6 kJ/s.
+/// Выражение, которое не удалось вычислить, даёт ошибку на позиции `delta`
+pub fn collect_rates(tokens: &[Token], ctx: &UnitContext) -> Vec<RateEntry<(f64, ResolvedUnit)>> {
+	delta_positions(tokens).map(|index| (tokens[index].position, field_name(tokens, index), evaluate_at(tokens, index, ctx, |reader| reader.rates))).collect()
+}
+
+/// Значения с погрешностью в исходнике: `9.81 m/s2 ± 0.02 m/s2`, `9.81 ± 0.02 m/s2`, `(200 ± 4) W`, `200W ± 2%`.
+/// Погрешность без юнита — в юните значения; юнит после погрешности или скобки — общий
+pub fn collect_uncertain(tokens: &[Token], ctx: &UnitContext) -> Vec<RateEntry<Measured>> {
+	tokens
+		.iter()
+		.enumerate()
+		.filter(|(_, token)| token.kind == TokenKind::Operator(OperatorKind::PlusMinus))
+		.map(|(index, token)| (token.position, field_name(tokens, index), evaluate_at(tokens, index, ctx, |reader| reader.uncertain)))
+		.collect()
+}
+
+/// Литерал с юнитом как написан, без округления до f64: `0.1 kL` — ровно 1/10 kL. `index` — позиция числа
+pub fn exact_quantity<'t>(tokens: &'t [Token], index: usize) -> Option<(Ratio, &'t ResolvedUnit)> {
+	let value = tokens.get(index).filter(|t| t.kind == TokenKind::Number).and_then(text)?.parse().ok()?;
+	Some((value, tokens.get(index + 1)?.kind.unit()?))
+}
+
+/// Сверяет скорости исходника с сигнатурами схемы по имени поля; ошибки — на позиции `delta`
+pub fn check_rates(schema: &[Token], source: &[Token], ctx: &UnitContext, errors: &mut ErrorStorage) {
+	let signatures = collect_rate_signatures(schema);
+	let mut report = |error, position| errors.add(Error::at(ErrorKind::Semantic(SemanticError::Unit(error)), position), ErrorFlag::Common);
+
+	for (position, _, signature) in &signatures {
+		if let Err(error) = signature {
+			report(error.clone(), *position);
+		}
+	}
+
+	for (position, field, rate) in collect_rates(source, ctx) {
+		let checked = rate.and_then(|(_, unit)| match signatures.iter().find(|(_, name, _)| name.is_some() && *name == field) {
+			Some((_, _, Ok(signature))) => ctx.check_rate(&unit, signature),
+			_ => Ok(()),
+		});
+		if let Err(error) = checked {
+			report(error, position);
+		}
+	}
+}
+
+// `delta`/`Δ` сразу после деления
+fn delta_positions<'t>(tokens: &'t [Token]) -> impl Iterator<Item = usize> + 't {
+	tokens
+		.iter()
+		.enumerate()
+		.skip(1)
+		.filter(|(index, token)| token.kind == TokenKind::Keyword(KeywordKind::Delta) && matches!(tokens[index - 1].kind, TokenKind::Operator(OperatorKind::Slash | OperatorKind::Obelus)))
+		.map(|(index, _)| index)
+}
+
+// Вычисляет выражение вокруг оператора и берёт значение, записанное на его позиции
+fn evaluate_at<T>(tokens: &[Token], index: usize, ctx: &UnitContext, recorded: impl FnOnce(QuantityReader) -> Vec<(Position, T)>) -> Result<T, UnitError> {
+	let expression = &tokens[expression_around(tokens, index)];
+	let mut reader = QuantityReader::new(expression, ctx);
+	reader.read()?;
+	recorded(reader)
+		.into_iter()
+		.find(|(at, _)| *at == tokens[index].position)
+		.map(|(_, value)| value)
+		.ok_or_else(|| UnitError::UnreadableQuantity(source_text(expression)))
+}
+
+// Первый идентификатор строки: `consumption` в `consumption<Expr>?: ...`, `d` в `local const d<Expr> = ...`
+fn field_name(tokens: &[Token], index: usize) -> Option<String> {
+	let line = tokens[index].position.line;
+	tokens[..index].iter().rev().take_while(|t| t.position.line == line).filter(|t| t.kind == TokenKind::Identifier).last().and_then(text)
+}
+
+// Границы выражения вокруг оператора: соседние токены, которые могут в него входить.
+// Скобка, открытая с одной стороны и закрытая с другой, — часть выражения: `(200 ± 4) W`
+fn expression_around(tokens: &[Token], index: usize) -> Range<usize> {
+	let (mut start, mut end) = (index, index + 1);
+	loop {
+		start -= balanced_run(tokens[..start].iter().rev(), SyntaxKind::RightParenthesis);
+		end += balanced_run(tokens[end..].iter(), SyntaxKind::LeftParenthesis);
+
+		match (start.checked_sub(1).map(|before| &tokens[before].kind), tokens.get(end).map(|t| &t.kind)) {
+			(Some(TokenKind::Syntax(SyntaxKind::LeftParenthesis)), Some(TokenKind::Syntax(SyntaxKind::RightParenthesis))) => (start, end) = (start - 1, end + 1),
+			_ => return start..end,
+		}
+	}
+}
+
+// Токены выражения подряд, пока не встретится непарная скобка; `opening` открывает группу в направлении обхода
+fn balanced_run<'t, 'a: 't>(tokens: impl Iterator<Item = &'t Token<'a>>, opening: SyntaxKind) -> usize {
+	let mut depth = 0;
+	tokens
+		.take_while(|t| {
+			if let TokenKind::Syntax(kind @ (SyntaxKind::LeftParenthesis | SyntaxKind::RightParenthesis)) = t.kind {
+				depth += if kind == opening { 1 } else { -1 };
+			}
+			depth >= 0 && is_expression_part(t)
+		})
+		.count()
+}
+
+// Имена и вызовы тоже входят в выражение — чтобы попасть в текст ошибки, а не обрезать его
+fn is_expression_part(token: &Token) -> bool {
+	matches!(
+		token.kind,
+		TokenKind::Number
+			| TokenKind::Unit(_)
+			| TokenKind::Identifier
+			| TokenKind::Keyword(KeywordKind::Delta)
+			| TokenKind::Operator(OperatorKind::Plus | OperatorKind::Minus | OperatorKind::PlusMinus | OperatorKind::Asterisk | OperatorKind::Multiplication | OperatorKind::Slash | OperatorKind::Obelus | OperatorKind::Dot | OperatorKind::Circumflex)
+			| TokenKind::Syntax(SyntaxKind::LeftParenthesis | SyntaxKind::RightParenthesis)
+	)
+}
+
+// Текст токенов как в исходнике, с точностью до ширины пробелов: пробел там, где между лексемами есть зазор
+fn source_text(tokens: &[Token]) -> String {
+	let mut source = String::new();
+	let mut end = None;
+	for token in tokens {
+		if end.is_some_and(|end| end < token.position.offset) {
+			source.push(' ');
+		}
+		source.push_str(&text(token).unwrap_or_default());
+		end = Some(token.position.offset + token.lexeme.map_or(0, <[u8]>::len));
+	}
+	source
+}
+
+// Вычисляет выражение по токенам, пока нет парсера: числа с юнитами, `±`, суммы, умножение и деление на число, скобки.
+// Всё остальное — `UnreadableQuantity` с текстом всего выражения
+struct QuantityReader<'t, 'a> {
+	tokens: &'t [Token<'a>],
+	ctx: &'t UnitContext,
+	index: usize,
+	// Скорость у каждого `delta`, в порядке вычисления
+	rates: Vec<(Position, (f64, ResolvedUnit))>,
+	// Значение у каждого `±`; юнит после скобки (`(200 ± 4) W`) дописывается сюда же
+	uncertain: Vec<(Position, Measured)>,
+}
+
+impl<'t, 'a> QuantityReader<'t, 'a> {
+	fn new(tokens: &'t [Token<'a>], ctx: &'t UnitContext) -> Self {
+		Self {
+			tokens,
+			ctx,
+			index: 0,
+			rates: Vec::new(),
+			uncertain: Vec::new(),
+		}
+	}
+
+	fn read(&mut self) -> Result<Measured, UnitError> {
+		let quantity = self.measured()?;
+		match self.index == self.tokens.len() {
+			true => Ok(quantity),
+			false => Err(self.unreadable()),
+		}
+	}
+
+	// `±` связывает слабее суммы: `1m + 5cm ± 1cm` — погрешность всей суммы
+	fn measured(&mut self) -> Result<Measured, UnitError> {
+		let value = self.sum()?;
+		let Some(operator) = self.next_if(|kind| *kind == TokenKind::Operator(OperatorKind::PlusMinus)) else {
+			return Ok(value);
+		};
+
+		let uncertainty = self.sum()?;
+		let unit = value.1.clone().or_else(|| uncertainty.1.clone());
+		let attached = match &unit {
+			Some(unit) => self.ctx.uncertain(value.0.value, unit, uncertainty.0.value, uncertainty.1.as_ref())?,
+			None => Uncertain::new(value.0.value, uncertainty.0.value),
+		};
+		let quantity = (Uncertain::new(attached.value, attached.uncertainty.hypot(value.0.uncertainty)), unit);
+		self.uncertain.push((operator.position, quantity.clone()));
+		Ok(quantity)
+	}
+
+	fn sum(&mut self) -> Result<Measured, UnitError> {
+		let mut lhs = self.product()?;
+		while let Some(operator) = self.next_if(|kind| matches!(kind, TokenKind::Operator(OperatorKind::Plus | OperatorKind::Minus))) {
+			let rhs = self.product()?;
+			let rhs = self.convert(rhs, &lhs.1)?;
+			lhs.0 = match operator.kind {
+				TokenKind::Operator(OperatorKind::Minus) => lhs.0 - rhs,
+				_ => lhs.0 + rhs,
+			};
+		}
+		Ok(lhs)
+	}
+
+	fn product(&mut self) -> Result<Measured, UnitError> {
+		let mut lhs = self.unary()?;
+		while let Some(operator) = self.next_if(|kind| matches!(kind, TokenKind::Operator(OperatorKind::Asterisk | OperatorKind::Multiplication | OperatorKind::Slash | OperatorKind::Obelus))) {
+			let divide = matches!(operator.kind, TokenKind::Operator(OperatorKind::Slash | OperatorKind::Obelus));
+			if divide && let Some(delta) = self.next_if(|kind| *kind == TokenKind::Keyword(KeywordKind::Delta)) {
+				let interval = self.unary()?;
+				lhs = self.rate(delta.position, lhs, interval)?;
+				continue;
+			}
+
+			let rhs = self.unary()?;
+			lhs = match (lhs.1, rhs.1) {
+				(unit, None) if divide => (lhs.0 / rhs.0, unit),
+				(unit, None) => (lhs.0 * rhs.0, unit),
+				(None, unit) if !divide => (lhs.0 * rhs.0, unit),
+				// Произведение юнитов (`2m * 3s`) без парсера юнит-выражений не собрать
+				_ => return Err(self.unreadable()),
+			};
+		}
+		Ok(lhs)
+	}
+
+	fn unary(&mut self) -> Result<Measured, UnitError> {
+		match self.next_if(|kind| *kind == TokenKind::Operator(OperatorKind::Minus)) {
+			Some(_) => self.unary().map(|(value, unit)| (-value, unit)),
+			None => self.primary(),
+		}
+	}
+
+	// `10kJ`, `2 s`, `kJ`, `(10kJ + 2kJ)`, `(2 + 3) s`
+	fn primary(&mut self) -> Result<Measured, UnitError> {
+		let recorded = self.uncertain.len();
+		let token = self.next_if(|_| true).ok_or_else(|| self.unreadable())?;
+		let quantity = match token.kind {
+			TokenKind::Number => (Uncertain::exact(number(token).ok_or_else(|| self.unreadable())?), None),
+			TokenKind::Unit(_) => return Ok((Uncertain::exact(1.0), token.kind.unit().cloned())),
+			TokenKind::Syntax(SyntaxKind::LeftParenthesis) => {
+				let inner = self.measured()?;
+				self.next_if(|kind| *kind == TokenKind::Syntax(SyntaxKind::RightParenthesis)).ok_or_else(|| self.unreadable())?;
+				inner
+			}
+			_ => return Err(self.unreadable()),
+		};
+
+		match (self.unit_suffix(), &quantity.1) {
+			(Some(unit), None) => {
+				for (_, (_, inner)) in self.uncertain[recorded..].iter_mut().filter(|(_, (_, inner))| inner.is_none()) {
+					*inner = Some(unit.clone());
+				}
+				Ok((quantity.0, Some(unit)))
+			}
+			(Some(_), Some(_)) => Err(self.unreadable()),
+			(None, _) => Ok(quantity),
+		}
+	}
+
+	// Юнит после числа или скобки: `2 s`, `(200 ± 4) W`. После скобки сканер оставляет юнит именем — разрешаем его здесь
+	fn unit_suffix(&mut self) -> Option<ResolvedUnit> {
+		let token = self.tokens.get(self.index)?;
+		let unit = match token.kind {
+			TokenKind::Unit(_) => token.kind.unit().cloned(),
+			TokenKind::Identifier => token.lexeme.and_then(|symbol| self.ctx.resolve(symbol)),
+			_ => None,
+		}?;
+		self.index += 1;
+		Some(unit)
+	}
+
+	// Погрешности делимого и интервала переходят в скорость относительными
+	fn rate(&mut self, delta: Position, (quantity, unit): Measured, (interval, per): Measured) -> Result<Measured, UnitError> {
+		let unit = unit.ok_or_else(|| self.unreadable())?;
+		let per = per.ok_or_else(|| UnitError::InvalidInterval(interval.value.to_string()))?;
+		let (value, rate) = self.ctx.rate((quantity.value, &unit), (interval.value, &per))?;
+
+		let ratio = quantity / interval;
+		let value = match ratio.uncertainty == 0.0 {
+			true => Uncertain::exact(value),
+			false => Uncertain::relative(value, ratio.relative_uncertainty()),
+		};
+		self.rates.push((delta, (value.value, rate.clone())));
+		Ok((value, Some(rate)))
+	}
+
+	// Слагаемые приводятся к юниту первого: `1m + 20cm` = 1.2 m
+	fn convert(&self, (quantity, from): Measured, to: &Option<ResolvedUnit>) -> Result<Uncertain, UnitError> {
+		match (&from, to) {
+			(Some(from), Some(to)) => self.ctx.convert_uncertain(quantity, from, to, &RelativeContext::UNKNOWN),
+			(None, None) => Ok(quantity),
+			_ => Err(UnitError::IncompatibleUnits(self.describe(&from), self.describe(to))),
+		}
+	}
+
+	fn describe(&self, unit: &Option<ResolvedUnit>) -> String {
+		unit.as_ref().map_or_else(|| "1".to_string(), |unit| self.ctx.format_unit(unit, &FormatOptions::DEFAULT))
+	}
+
+	fn next_if(&mut self, accept: impl FnOnce(&TokenKind) -> bool) -> Option<&'t Token<'a>> {
+		let token = self.tokens.get(self.index).filter(|t| accept(&t.kind))?;
+		self.index += 1;
+		Some(token)
+	}
+
+	fn unreadable(&self) -> UnitError {
+		UnitError::UnreadableQuantity(source_text(self.tokens))
+	}
+}
+
+fn parse_declaration<'t, 'a: 't>(iter: &mut Peekable<impl Iterator<Item = &'t Token<'a>>>) -> Result<UnitDeclaration, UnitError> {
+	let symbol = iter
+		.next_if(|t| matches!(t.kind, TokenKind::Identifier | TokenKind::Keyword(_)))
+		.and_then(text)
+		.ok_or(UnitError::InvalidDeclaration("expected unit symbol"))?;
+
+	let mut declaration = if iter.next_if(|t| t.kind == TokenKind::Operator(OperatorKind::Colon)).is_some() {
+		let name = iter.next_if(|t| t.kind == TokenKind::Identifier).and_then(text).ok_or(UnitError::InvalidDeclaration("expected dimension name"))?;
+		let base = UnitKind::from_str(&name).map_or(UnitBase::Dimension(name), UnitBase::Kind);
+
+		UnitDeclaration::new(symbol, base)
+	} else if iter.next_if(|t| t.kind == TokenKind::Operator(OperatorKind::Equal)).is_some() {
+		let scale = iter.next_if(|t| t.kind == TokenKind::Number).and_then(number).ok_or(UnitError::InvalidDeclaration("expected scale"))?;
+
+		// Юнит-выражение идёт без пробелов: `cell/tick`, а не `cell / tick`
+		let mut expression = String::new();
+		while let Some(part) = iter.next_if(|t| !is_line_end(t) && is_unit_part(t) && (expression.is_empty() || !t.flags.contains(TokenFlags::HAS_PRECEDING_WHITESPACE))) {
+			expression.push_str(&text(part).unwrap_or_default());
+		}
+		if expression.is_empty() {
+			return Err(UnitError::InvalidDeclaration("expected base unit"));
+		}
+
+		let mut declaration = UnitDeclaration::new(symbol, UnitBase::Unit(expression)).with_scale(scale);
+
+		if let Some(sign) = iter.next_if(|t| matches!(t.kind, TokenKind::Operator(OperatorKind::Plus | OperatorKind::Minus))) {
+			let offset = iter.next_if(|t| t.kind == TokenKind::Number).and_then(number).ok_or(UnitError::InvalidDeclaration("expected offset"))?;
+			declaration = declaration.with_offset(if sign.kind == TokenKind::Operator(OperatorKind::Minus) { -offset } else { offset });
+		}
+
+		declaration
+	} else {
+		return Err(UnitError::InvalidDeclaration("expected `:` or `=`"));
+	};
+
+	if iter.next_if(|t| t.kind == TokenKind::Keyword(KeywordKind::With)).is_some() {
+		let group = iter.next_if(|t| t.kind == TokenKind::Identifier).and_then(text).ok_or(UnitError::InvalidDeclaration("expected prefix group"))?;
+		declaration = declaration.with_prefixes(PrefixGroup::from_str(&group).map_err(|_| UnitError::InvalidDeclaration("unknown prefix group"))?);
+	}
+
+	match iter.peek() {
+		Some(t) if !is_line_end(t) => Err(UnitError::InvalidDeclaration("unexpected token")),
+		_ => Ok(declaration),
+	}
+}
+
+fn is_line_end(token: &Token) -> bool {
+	matches!(token.kind, TokenKind::Newline | TokenKind::EOF | TokenKind::LineComment | TokenKind::Dedent(_))
+}
+
+fn is_unit_part(token: &Token) -> bool {
+	matches!(
+		token.kind,
+		TokenKind::Unit(_) | TokenKind::Identifier | TokenKind::Number | TokenKind::Operator(OperatorKind::Slash | OperatorKind::Asterisk | OperatorKind::Circumflex) | TokenKind::Syntax(SyntaxKind::LeftParenthesis | SyntaxKind::RightParenthesis)
+	)
+}
+
+fn text(token: &Token) -> Option<String> {
+	token.lexeme.and_then(|l| std::str::from_utf8(l).ok()).map(str::to_owned)
+}
+
+fn number(token: &Token) -> Option<f64> {
+	text(token)?.replace('_', "").parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::frontend::lexer::scanned;
+	use crate::shared::{FormatOptions, UnitLibrary};
+
+	const RATE_SCHEMA: &[u8] = b"consumption<Expr>?: <Mass> \xC3\xB7 delta <Time>\nheat<Expr>?: <Energy> / delta <Time>\n";
+	const RATE_SOURCE: &[u8] = b"consumption = 3kg / \xCE\x94 2h\nheat = 150kJ / delta 1min\n";
+
+	fn unit_kinds(tokens: &[Token]) -> Vec<UnitKind> {
+		tokens.iter().filter_map(|token| token.kind.unit()).map(|unit| unit.kind).collect()
+	}
+
+	#[test]
+	fn declared_units_reach_the_same_scan() {
+		let ctx = UnitContext::new();
+		let mut errors = ErrorStorage::new();
+		let mut scanner = Scanner::new(b"declare unit tick = 0.05 s\nwait = 3 tick\n", &ctx, &mut errors, None);
+
+		assert_eq!(unit_kinds(scanner.scan_tokens()), [UnitKind::Time, UnitKind::Time]);
+		assert_eq!(scanner.declarations().len(), 1);
+		assert!(scanner.context().resolve(b"tick").is_some() && ctx.resolve(b"tick").is_none());
+	}
+
+	#[test]
+	fn declarations_from_source() {
+		let mut ctx = UnitContext::new();
+		let mut errors = ErrorStorage::new();
+		let source = b"declare unit tick = 0.05 s\ndeclare unit shift = 480 tick\ndeclare unit cell: Cell with SI\ndeclare unit oops = 2 parsec\n";
+		assert_eq!(declare_units(source, &mut ctx, &mut errors).len(), 3);
+		assert_eq!(errors.all().len(), 1);
+
+		let shift = ctx.unit("shift");
+		assert_eq!(shift.kind, UnitKind::Time);
+		assert!((ctx.convert(1.0, &shift, &ctx.unit("min")).expect("shift -> min") - 0.4).abs() < 1e-9);
+	}
+
+	#[test]
+	fn profile_from_source() {
+		let reported = scanned(b"declare units SI\nwidth = 5ft\nheight = 2m\n", |tokens, ctx, errors| {
+			let (_, profile) = select_unit_profile(tokens).expect("declare units");
+			check_unit_profile(tokens, ctx, &profile.expect("SI"), errors);
+			errors.all().len()
+		});
+		assert_eq!(reported, 1);
+	}
+
+	#[test]
+	fn uncertain_values_from_source() {
+		let found = scanned("g = 9.81 m/s2 ± 0.02 m/s2\np = 200W ± 2%\nl = 1.5m ± 3mm\nt = 5s ± 1kg\n".as_bytes(), |tokens, ctx, _| collect_uncertain(tokens, ctx));
+
+		let values: Vec<_> = found.iter().map(|(_, field, quantity)| (field.as_deref(), quantity.as_ref().ok().map(|(quantity, _)| *quantity))).collect();
+		assert_eq!(values[0], (Some("g"), Some(Uncertain::new(9.81, 0.02))));
+		assert_eq!(values[1], (Some("p"), Some(Uncertain::new(200.0, 4.0))));
+		assert!(matches!(values[2], (Some("l"), Some(quantity)) if quantity.value == 1.5 && (quantity.uncertainty - 0.003).abs() < 1e-12));
+		assert!(matches!(found[3].2, Err(UnitError::IncompatibleUnits(..))));
+	}
+
+	#[test]
+	fn uncertainty_is_attached_to_expressions() {
+		let found: Vec<_> = scanned("g = 9.81 ± 0.02 m/s2\np = (200 ± 4) W\nl = (1m + 50cm) ± 3mm\nx = y ± 1\n".as_bytes(), |tokens, ctx, _| {
+			let format = |unit: ResolvedUnit| ctx.format_unit(&unit, &FormatOptions::DEFAULT);
+			collect_uncertain(tokens, ctx).into_iter().map(|(_, _, quantity)| quantity.map(|(quantity, unit)| (quantity, unit.map(format)))).collect()
+		});
+
+		assert!(matches!(&found[0], Ok((quantity, Some(unit))) if *quantity == Uncertain::new(9.81, 0.02) && unit == "m/s²"));
+		assert!(matches!(&found[1], Ok((quantity, Some(unit))) if *quantity == Uncertain::new(200.0, 4.0) && unit == "W"));
+		assert!(matches!(&found[2], Ok((quantity, Some(unit))) if quantity.value == 1.5 && (quantity.uncertainty - 0.003).abs() < 1e-12 && unit == "m"));
+		assert!(matches!(&found[3], Err(UnitError::UnreadableQuantity(quantity)) if quantity == "y ± 1"));
+	}
+
+	#[test]
+	fn exact_literals_compare_exactly() {
+		scanned(b"a = 1 ft\nb = 12 in\nc = 0.1 kL\n", |tokens, ctx, _| {
+			let numbers: Vec<usize> = (0..tokens.len()).filter(|&index| tokens[index].kind == TokenKind::Number).collect();
+			let [foot, inches, volume] = [0, 1, 2].map(|i| exact_quantity(tokens, numbers[i]).expect("quantity"));
+
+			assert_eq!(ctx.compare_exact(foot, inches), Some(std::cmp::Ordering::Equal));
+			assert_eq!(ctx.compare_exact(volume, (Ratio::integer(99), &ctx.unit("L"))), Some(std::cmp::Ordering::Greater));
+			assert_eq!(ctx.compare_exact(foot, volume), None);
+		});
+	}
+
+	#[test]
+	fn rates_from_source() {
+		assert_eq!(scanned(RATE_SCHEMA, |schema, _, _| collect_rate_signatures(schema).len()), 2);
+
+		scanned(RATE_SOURCE, |tokens, ctx, _| {
+			let rates = collect_rates(tokens, ctx);
+			assert!(matches!(&rates[0], (_, Some(field), Ok((value, unit))) if field == "consumption" && (value - 3.0 / 7200.0).abs() < 1e-12 && ctx.format_unit(unit, &FormatOptions::DEFAULT) == "kg/s"));
+			assert!(matches!(&rates[1], (_, Some(field), Ok((value, _))) if field == "heat" && (value - 2.5).abs() < 1e-12));
+		});
+	}
+
+	#[test]
+	fn rates_are_checked_against_schema() {
+		let reported = |source: &[u8]| {
+			scanned(RATE_SCHEMA, |schema, ctx, errors| {
+				scanned(source, |tokens, _, _| check_rates(schema, tokens, ctx, errors));
+				errors.all().len()
+			})
+		};
+
+		assert_eq!(reported(RATE_SOURCE), 0);
+		assert_eq!(reported(b"heat = 5kg / delta 1s\n"), 1);
+	}
+
+	#[test]
+	fn rate_operands_are_expressions() {
+		let source = b"a = (10kJ + 2kJ) / delta 2s\nb = 10kJ / delta (2 s)\nc = x / delta (2 s)\nd = 10kJ / delta 2s + energy\ne = 2m * 3s / delta 1s\n";
+		let rates: Vec<_> = scanned(source, |tokens, ctx, _| {
+			let format = |(value, unit): (f64, ResolvedUnit)| format!("{} {}", value, ctx.format_unit(&unit, &FormatOptions::DEFAULT));
+			collect_rates(tokens, ctx).into_iter().map(|(_, _, rate)| rate.map(format)).collect()
+		});
+		let unreadable = |rate: &Result<String, UnitError>| match rate {
+			Err(UnitError::UnreadableQuantity(quantity)) => quantity.clone(),
+			other => panic!("expected an unreadable quantity, got {:?}", other),
+		};
+
+		assert_eq!(rates[0].as_deref().ok(), Some("6 kW"));
+		assert_eq!(rates[1].as_deref().ok(), Some("5 kW"));
+		assert_eq!(unreadable(&rates[2]), "x / delta (2 s)");
+		assert_eq!(unreadable(&rates[3]), "10kJ / delta 2s + energy");
+		assert_eq!(unreadable(&rates[4]), "2m * 3s / delta 1s");
+	}
+
+	#[test]
+	fn rate_signatures_are_parsed_whole() {
+		let signatures = scanned(b"heat<Expr>: < Energy > / delta <Time>\nflow<Expr>: <Rate<Energy>> / delta <Time>\n", |schema, _, _| collect_rate_signatures(schema));
+
+		assert_eq!(signatures[0].2.as_ref().ok(), Some(&RateSignature::new(UnitKind::Energy, UnitKind::Time)));
+		assert!(matches!(&signatures[1], (_, Some(field), Err(UnitError::InvalidRateSignature(_))) if field == "flow"));
+	}
+
+	#[test]
+	fn units_are_imported_from_library() {
+		let ctx = UnitContext::new();
+		let mut library = UnitLibrary::new();
+		let mut errors = ErrorStorage::new();
+		let mut units = Scanner::new(b"declare unit tick = 0.05 s\ndeclare unit cell: Cell with SI\n", &ctx, &mut errors, None);
+		units.scan_tokens();
+		library.insert("./game_units.lacon", units.declarations().to_vec());
+
+		let mut errors = ErrorStorage::new();
+		let source = b"import units from \"./game_units.lacon\"\nimport units from \"./game_units.lacon\"\nspeed = 4 kcell/tick\n";
+		let mut scanner = Scanner::new(source, &ctx, &mut errors, None).with_library(&library);
+		let tokens = scanner.scan_tokens();
+		assert_eq!(tokens.iter().filter_map(|token| token.kind.unit()).map(|unit| unit.scale).collect::<Vec<_>>(), [20_000.0]);
+		assert!(scanner.declarations().is_empty());
+
+		let mut errors = ErrorStorage::new();
+		Scanner::new(b"import units from \"./missing.lacon\"\n", &ctx, &mut errors, None).scan_tokens();
+		assert_eq!(errors.all().iter().map(|stored| stored.error.kind.code()).collect::<Vec<_>>(), ["U0014"]);
+	}
+}
@@ -67,6 +67,10 @@ unicode_chars! {
 				SUP_0 = "⁰"; SUP_1 = "¹"; SUP_2 = "²"; SUP_3 = "³";
 				SUP_4 = "⁴"; SUP_5 = "⁵"; SUP_6 = "⁶"; SUP_7 = "⁷";
 				SUP_8 = "⁸"; SUP_9 = "⁹"; SUP_N = "ⁿ";
+				SUP_MINUS = "⁻";
+
+				DOT_OPERATOR = "⋅";
+				MIDDLE_DOT = "·";
 
 				SUB_0 = "₀"; SUB_1 = "₁"; SUB_2 = "₂"; SUB_3 = "₃";
 				SUB_4 = "₄"; SUB_5 = "₅"; SUB_6 = "₆"; SUB_7 = "₇";
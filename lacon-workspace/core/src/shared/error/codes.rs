@@ -0,0 +1,358 @@
+use super::{ErrorKind, LexicalError, LintError, SemanticError, SyntaxError, UnitError};
+
+/// Подробное описание диагностики для `lacon explain L0003` и подсказок LSP
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Explanation {
+	pub code: &'static str,
+	pub title: &'static str,
+	pub description: &'static str,
+	pub wrong: &'static str,
+	pub fixed: &'static str,
+}
+
+// Коды стабильны: не переиспользуются и не перенумеровываются, новые варианты получают следующий свободный номер.
+// L — лексер, S — синтаксис, T — типы, U — юниты, W — линты и подавления, R — время выполнения
+
+impl ErrorKind {
+	pub fn code(&self) -> &'static str {
+		match self {
+			ErrorKind::Unknown => "E0000",
+			ErrorKind::Lexical(error) => error.code(),
+			ErrorKind::Syntax(error) => error.code(),
+			ErrorKind::Semantic(error) => error.code(),
+			ErrorKind::Lint(error) => error.code(),
+			ErrorKind::Runtime => "R0001",
+		}
+	}
+
+	pub fn explanation(&self) -> Option<&'static Explanation> {
+		explain(self.code())
+	}
+}
+
+impl LexicalError {
+	pub fn code(&self) -> &'static str {
+		match self {
+			LexicalError::InvalidCharacter(_) => "L0001",
+			LexicalError::InvalidToken => "L0002",
+			LexicalError::UnterminatedString => "L0003",
+			LexicalError::UnterminatedBlockComment => "L0004",
+			LexicalError::InvalidEscapeSequence(_) => "L0005",
+			LexicalError::InvalidIndent => "L0006",
+			LexicalError::InvalidIndentation => "L0007",
+		}
+	}
+}
+
+impl SyntaxError {
+	pub fn code(&self) -> &'static str {
+		match self {
+			SyntaxError::Expected { .. } => "S0001",
+			SyntaxError::UnknownKeyword(_) => "S0002",
+		}
+	}
+}
+
+impl LintError {
+	pub fn code(&self) -> &'static str {
+		match self {
+			LintError::UnusedSuppression(_) => "W0001",
+			LintError::UnknownCode(_) => "W0002",
+			LintError::InvalidLevel(_) => "W0003",
+			LintError::InvalidDirective(_) => "W0004",
+		}
+	}
+}
+
+impl SemanticError {
+	pub fn code(&self) -> &'static str {
+		match self {
+			SemanticError::TypeMismatch { .. } => "T0001",
+			SemanticError::Unit(error) => error.code(),
+			SemanticError::UndefinedName(_) => "T0002",
+		}
+	}
+}
+
+impl UnitError {
+	pub fn code(&self) -> &'static str {
+		match self {
+			UnitError::DuplicateSymbol(_) => "U0001",
+			UnitError::InvalidSymbol(_) => "U0002",
+			UnitError::UnknownUnit(_) => "U0003",
+			UnitError::TooManyDimensions(_) => "U0004",
+			UnitError::InvalidDeclaration(_) => "U0005",
+			UnitError::IncompatibleUnits(..) => "U0006",
+			UnitError::Deferred(_) => "U0007",
+			UnitError::AmbiguousCalendar(..) => "U0008",
+			// С подсказкой или без — одна и та же проблема, подавляется одним кодом
+			UnitError::OutsideProfile(..) | UnitError::OutsideProfileConvert(..) => "U0009",
+			UnitError::UnknownProfile(_) => "U0010",
+			UnitError::InvalidRateSignature(_) => "U0011",
+			UnitError::InvalidInterval(_) => "U0012",
+			UnitError::RateMismatch(..) => "U0013",
+			UnitError::UnknownUnitImport(_) => "U0014",
+			UnitError::UnreadableQuantity(_) => "U0015",
+		}
+	}
+}
+
+/// Описание по коду, регистр не важен: `explain("l0003")`
+pub fn explain(code: &str) -> Option<&'static Explanation> {
+	EXPLANATIONS.iter().find(|explanation| explanation.code.eq_ignore_ascii_case(code))
+}
+
+pub static EXPLANATIONS: &[Explanation] = &[
+	Explanation {
+		code: "E0000",
+		title: "Unknown error",
+		description: "An internal error without a more specific kind. Please report it together with the source that triggers it.",
+		wrong: "",
+		fixed: "",
+	},
+	Explanation {
+		code: "L0001",
+		title: "Invalid character",
+		description: "The source contains a character that cannot start any token. Usually it is a stray symbol copied from another document.",
+		wrong: "let price = 5 ¤ 2",
+		fixed: "let price = 5 / 2",
+	},
+	Explanation {
+		code: "L0002",
+		title: "Invalid token",
+		description: "A sequence of characters looks like a token but is not a valid one, for example a malformed number or operator.",
+		wrong: "let mask = 0b102",
+		fixed: "let mask = 0b101",
+	},
+	Explanation {
+		code: "L0003",
+		title: "Unterminated string literal",
+		description: "A string was opened but never closed before the end of the line or file. Close it with the same quote it was opened with.",
+		wrong: "const a<String> = \"Just string",
+		fixed: "const a<String> = \"Just string\"",
+	},
+	Explanation {
+		code: "L0004",
+		title: "Unterminated block comment",
+		description: "A `/*` comment reaches the end of the file without a matching `*/`.",
+		wrong: "/* disabled for now\nlet a = 1",
+		fixed: "/* disabled for now */\nlet a = 1",
+	},
+	Explanation {
+		code: "L0005",
+		title: "Invalid escape sequence",
+		description: "A backslash inside a string is followed by a character that has no escape meaning. Use `\\\\` for a literal backslash.",
+		wrong: "const path<String> = \"C:\\data\"",
+		fixed: "const path<String> = \"C:\\\\data\"",
+	},
+	Explanation {
+		code: "L0006",
+		title: "Invalid indent",
+		description: "The line is indented where no nested block can start.",
+		wrong: "entity-type: energy-generator\n    entity-name: <String>",
+		fixed: "entity-type: energy-generator\nentity-name: <String>",
+	},
+	Explanation {
+		code: "L0007",
+		title: "Invalid indentation",
+		description: "The line is dedented to a level that does not match any enclosing block.",
+		wrong: "if ready\n        start()\n    stop()",
+		fixed: "if ready\n        start()\nstop()",
+	},
+	Explanation {
+		code: "S0001",
+		title: "Unexpected token",
+		description: "The parser expected a different construct at this position.",
+		wrong: "const a<String> 'text'",
+		fixed: "const a<String> = 'text'",
+	},
+	Explanation {
+		code: "S0002",
+		title: "Unknown keyword",
+		description: "A line starts with two plain words where a declaration keyword is expected. Usually the first word is a misspelled keyword.",
+		wrong: "fucntion total(a, b)",
+		fixed: "function total(a, b)",
+	},
+	Explanation {
+		code: "T0001",
+		title: "Type mismatch",
+		description: "The value does not match the declared type of the field or variable.",
+		wrong: "let c<String> = 18kg",
+		fixed: "let c = 18kg",
+	},
+	Explanation {
+		code: "T0002",
+		title: "Undefined name",
+		description: "The name is not declared in the current scope or any enclosing one. Check the spelling or declare it before use.",
+		wrong: "let total = prise * 2",
+		fixed: "let total = price * 2",
+	},
+	Explanation {
+		code: "U0001",
+		title: "Duplicate unit",
+		description: "A `declare unit` uses a symbol that is already a built-in or previously declared unit, including prefixed forms like `km`.",
+		wrong: "declare unit km: Length",
+		fixed: "declare unit league = 4828.032 m",
+	},
+	Explanation {
+		code: "U0002",
+		title: "Invalid unit symbol",
+		description: "A unit symbol may not contain digits, whitespace, brackets or operators: `m2x` would be read as `m²` followed by `x`.",
+		wrong: "declare unit m2x = 2 m",
+		fixed: "declare unit double = 2 m",
+	},
+	Explanation {
+		code: "U0003",
+		title: "Unknown unit",
+		description: "The unit is neither built in nor declared with `declare unit` above its first use.",
+		wrong: "let shift = 3 tick",
+		fixed: "declare unit tick = 0.05 s\nlet shift = 3 tick",
+	},
+	Explanation {
+		code: "U0004",
+		title: "Too many custom dimensions",
+		description: "Only a limited number of new base dimensions can be declared per context. Express new units through existing dimensions where possible.",
+		wrong: "declare unit cell: Cell\ndeclare unit tile: Tile\n...",
+		fixed: "declare unit cell: Length",
+	},
+	Explanation {
+		code: "U0005",
+		title: "Invalid unit declaration",
+		description: "The `declare unit` line does not follow `declare unit NAME: Dimension [with PREFIXES]` or `declare unit NAME = SCALE UNIT`.",
+		wrong: "declare unit tick 0.05 s",
+		fixed: "declare unit tick = 0.05 s",
+	},
+	Explanation {
+		code: "U0006",
+		title: "Incompatible units",
+		description: "The two units measure different quantities, so there is no conversion between them.",
+		wrong: "let total = 5m + 2s",
+		fixed: "let total = 5m + 2m",
+	},
+	Explanation {
+		code: "U0007",
+		title: "Layout-dependent unit",
+		description: "`em`, `rem` and `px` depend on the layout, which is not known yet. The value is kept as written and converted once the layout is available.",
+		wrong: "let width = 2em -> m",
+		fixed: "let width = 2em",
+	},
+	Explanation {
+		code: "U0008",
+		title: "Ambiguous calendar duration",
+		description: "Months and years have no fixed length in seconds, so they only convert within their own group (years to months, weeks to days). A physical duration added to a date must be a whole number of days.",
+		wrong: "let term = 2 calmonth -> day",
+		fixed: "let term = 2 calmonth -> calweek",
+	},
+	Explanation {
+		code: "U0009",
+		title: "Unit outside profile",
+		description: "The file selects a unit profile with `declare units`, and this unit belongs to a system the profile does not allow. The message suggests an equivalent value in an allowed unit.",
+		wrong: "declare units SI\nwidth = 5ft",
+		fixed: "declare units SI\nwidth = 1.524m",
+	},
+	Explanation {
+		code: "U0010",
+		title: "Unknown unit profile",
+		description: "`declare units` accepts `SI`, `metric` and `US`.",
+		wrong: "declare units imperial-ish",
+		fixed: "declare units US",
+	},
+	Explanation {
+		code: "U0011",
+		title: "Invalid rate signature",
+		description: "A rate field in a schema must be written as `<Quantity> ÷ delta <Quantity>` with known quantity names.",
+		wrong: "consumption<Expr>: <Mass> / <Time>",
+		fixed: "consumption<Expr>: <Mass> / delta <Time>",
+	},
+	Explanation {
+		code: "U0012",
+		title: "Invalid interval",
+		description: "The interval after `delta` must be a non-zero quantity with a dimension.",
+		wrong: "heat = 150kJ / delta 0s",
+		fixed: "heat = 150kJ / delta 1min",
+	},
+	Explanation {
+		code: "U0013",
+		title: "Rate does not match schema",
+		description: "The rate's dimension differs from the signature declared for the field in the schema.",
+		wrong: "consumption = 5kJ / delta 1s",
+		fixed: "consumption = 5kg / delta 1s",
+	},
+	Explanation {
+		code: "U0014",
+		title: "Unknown unit import",
+		description: "`import units from` names a file whose unit declarations were not scanned. The host must scan that file first and add its declarations to the unit library.",
+		wrong: "import units from \"./missing.lacon\"",
+		fixed: "import units from \"./game_units.lacon\"",
+	},
+	Explanation {
+		code: "U0015",
+		title: "Unreadable quantity",
+		description: "Around `delta` and `±` the operands must be numbers with units, sums of them or groups in parentheses. Names, calls and products of two units are not evaluated there yet.",
+		wrong: "heat = energy / delta 2s",
+		fixed: "heat = 10kJ / delta 2s",
+	},
+	Explanation {
+		code: "W0001",
+		title: "Unused suppression",
+		description: "A `lacon-allow` directive names a code that is not reported in its range. Remove the directive or the code from it.",
+		wrong: "/* lacon-allow-next-line U0009 */\nwidth = 5m",
+		fixed: "width = 5m",
+	},
+	Explanation {
+		code: "W0002",
+		title: "Unknown diagnostic code",
+		description: "A suppression directive or the `[lints]` section of the project config names a code that does not exist.",
+		wrong: "/* lacon-allow-next-line U9999 */",
+		fixed: "/* lacon-allow-next-line U0009 */",
+	},
+	Explanation {
+		code: "W0003",
+		title: "Invalid lint level",
+		description: "Lint levels in the `[lints]` section of the project config are `allow`, `warn` and `deny`.",
+		wrong: "[lints]\nU0009 = \"ignore\"",
+		fixed: "[lints]\nU0009 = \"allow\"",
+	},
+	Explanation {
+		code: "W0004",
+		title: "Invalid suppression directive",
+		description: "A directive has no codes, or a `lacon-allow-begin` block is never closed with `lacon-allow-end`.",
+		wrong: "/* lacon-allow-begin U0009 */\nwidth = 5ft",
+		fixed: "/* lacon-allow-begin U0009 */\nwidth = 5ft\n/* lacon-allow-end */",
+	},
+	Explanation {
+		code: "R0001",
+		title: "Runtime error",
+		description: "The program failed while running.",
+		wrong: "",
+		fixed: "",
+	},
+];
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use strum::IntoEnumIterator;
+
+	#[test]
+	fn every_kind_is_explained() {
+		let kinds = ErrorKind::iter()
+			.chain(LexicalError::iter().map(ErrorKind::Lexical))
+			.chain(SyntaxError::iter().map(ErrorKind::Syntax))
+			.chain(SemanticError::iter().map(ErrorKind::Semantic))
+			.chain(UnitError::iter().map(|error| ErrorKind::Semantic(SemanticError::Unit(error))))
+			.chain(LintError::iter().map(ErrorKind::Lint));
+
+		for kind in kinds {
+			assert!(explain(kind.code()).is_some(), "{} has no explanation", kind.code());
+			assert_eq!(kind.explanation().map(|e| e.code), Some(kind.code()));
+		}
+	}
+
+	#[test]
+	fn codes_are_stable_and_unique() {
+		assert_eq!(ErrorKind::Lexical(LexicalError::UnterminatedString).code(), "L0003");
+		assert_eq!(explain("u0009").map(|e| e.title), Some("Unit outside profile"));
+		assert!(EXPLANATIONS.iter().enumerate().all(|(i, e)| EXPLANATIONS[..i].iter().all(|other| other.code != e.code)));
+	}
+}
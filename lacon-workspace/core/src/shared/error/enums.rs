@@ -1,11 +1,33 @@
+use super::Locale;
+use serde::Serialize;
 use std::path::PathBuf;
+use strum_macros::EnumString;
 
+/// `Critical` останавливает разбор и не зависит от уровней линтов; `Common` можно понизить или подавить
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ErrorFlag {
 	Common,
 	Critical,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+	Hint,
+	Info,
+	Warning,
+	Error,
+}
+
+/// Уровень линта из конфигурации проекта: `U0009 = "warn"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum LintLevel {
+	Allow,
+	Warn,
+	Deny,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ErrorReporterFlag {
 	Truncate,
@@ -17,4 +39,10 @@ pub enum ErrorReporter {
 	Console,
 	File(PathBuf, ErrorReporterFlag),
 	Log(PathBuf, ErrorReporterFlag),
+	/// JSON-объект на строку, для CI и ботов
+	JsonLines(PathBuf, ErrorReporterFlag),
+	/// SARIF 2.1.0: документ целиком, файл перезаписывается
+	Sarif(PathBuf),
+	/// Тот же репортёр с явным языком сообщений вместо языка окружения
+	Localized(Locale, Box<ErrorReporter>),
 }
@@ -0,0 +1,318 @@
+use super::{Error, Locale, Position, Severity, StoredError, Suggestion, explain};
+use crate::shared::{SourceFile, SourceId};
+use serde::Serialize;
+use serde_json::{Value, json};
+
+/// Одна диагностика для машинного вывода. Колонки — в символах, смещения — в байтах, как в `Position`
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticRecord {
+	pub code: &'static str,
+	pub severity: Severity,
+	pub message: String,
+	pub uri: Option<String>,
+	pub start: Option<Position>,
+	pub end: Option<Position>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub label: Option<String>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub related: Vec<RelatedRecord>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub notes: Vec<String>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub suggestions: Vec<Suggestion>,
+}
+
+/// Вторичная подпись; URI уже подставлен, даже если подпись в том же файле
+#[derive(Debug, Clone, Serialize)]
+pub struct RelatedRecord {
+	pub uri: Option<String>,
+	pub start: Position,
+	pub end: Position,
+	pub message: String,
+}
+
+impl DiagnosticRecord {
+	pub fn new(error: &Error, severity: Severity, source: Option<&SourceFile>) -> Self {
+		Self::localized(error, severity, source, Locale::En)
+	}
+
+	pub fn localized(error: &Error, severity: Severity, source: Option<&SourceFile>, locale: Locale) -> Self {
+		let start = error.pos.as_ref().map(|pos| pos.start);
+		let uri = source.map(source_uri);
+		let related = error
+			.secondary
+			.iter()
+			.map(|label| RelatedRecord {
+				uri: label.source.as_ref().map(source_id_uri).or_else(|| uri.clone()),
+				start: label.pos.start,
+				end: label.pos.end.unwrap_or(label.pos.start),
+				message: label.message.clone(),
+			})
+			.collect();
+
+		Self {
+			code: error.kind.code(),
+			severity,
+			message: error.kind.message(locale),
+			uri,
+			start,
+			end: error.pos.as_ref().and_then(|pos| pos.end).or(start),
+			label: error.label.clone(),
+			related,
+			notes: error.notes.clone(),
+			suggestions: error
+				.suggestions
+				.iter()
+				.map(|suggestion| Suggestion {
+					message: suggestion.localized(locale),
+					..suggestion.clone()
+				})
+				.collect(),
+		}
+	}
+
+	pub fn from_stored(stored: &StoredError, source: Option<&SourceFile>, locale: Locale) -> Self {
+		Self::localized(&stored.error, stored.severity, source, locale)
+	}
+}
+
+pub fn source_uri(source: &SourceFile) -> String {
+	source_id_uri(&source.source_id)
+}
+
+/// `file:///abs/path.lacon` для файлов, URI и виртуальные имена — как есть
+pub fn source_id_uri(source_id: &SourceId) -> String {
+	match source_id {
+		SourceId::File(path) => format!("file://{}", path.display()),
+		SourceId::Uri(uri) => uri.clone(),
+		SourceId::Virtual(name) => name.clone(),
+	}
+}
+
+/// По одному JSON-объекту на строку, с завершающим переводом строки
+pub fn json_lines(records: &[DiagnosticRecord]) -> String {
+	records.iter().filter_map(|record| serde_json::to_string(record).ok()).map(|line| line + "\n").collect()
+}
+
+/// Лог SARIF 2.1.0 с одним запуском; правила — только для встретившихся кодов
+pub fn sarif_log(records: &[DiagnosticRecord]) -> Value {
+	let mut codes: Vec<&str> = records.iter().map(|record| record.code).collect();
+	codes.sort_unstable();
+	codes.dedup();
+
+	let rules: Vec<Value> = codes
+		.iter()
+		.map(|code| match explain(code) {
+			Some(explanation) => json!({
+				"id": code,
+				"name": explanation.title,
+				"shortDescription": { "text": explanation.title },
+				"fullDescription": { "text": explanation.description },
+			}),
+			None => json!({ "id": code }),
+		})
+		.collect();
+
+	let results: Vec<Value> = records.iter().map(|record| sarif_result(record, &codes)).collect();
+
+	json!({
+		"$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+		"version": "2.1.0",
+		"runs": [{
+			"tool": { "driver": { "name": "lacon", "version": env!("CARGO_PKG_VERSION"), "rules": rules } },
+			"columnKind": "unicodeCodePoints",
+			"results": results,
+		}],
+	})
+}
+
+fn sarif_result(record: &DiagnosticRecord, codes: &[&str]) -> Value {
+	let mut result = json!({
+		"ruleId": record.code,
+		"ruleIndex": codes.iter().position(|code| *code == record.code),
+		"level": match record.severity {
+			Severity::Error => "error",
+			Severity::Warning => "warning",
+			Severity::Info | Severity::Hint => "note",
+		},
+		"message": { "text": record.message },
+	});
+
+	let location = sarif_location(record.uri.as_deref(), record.start.zip(record.end));
+	if location != json!({}) {
+		result["locations"] = json!([{ "physicalLocation": location }]);
+		if let Some(label) = &record.label {
+			result["locations"][0]["message"] = json!({ "text": label });
+		}
+	}
+
+	let related: Vec<Value> = record
+		.related
+		.iter()
+		.enumerate()
+		.map(|(id, related)| {
+			json!({
+				"id": id,
+				"physicalLocation": sarif_location(related.uri.as_deref(), Some((related.start, related.end))),
+				"message": { "text": related.message },
+			})
+		})
+		.collect();
+	if !related.is_empty() {
+		result["relatedLocations"] = json!(related);
+	}
+	if !record.notes.is_empty() {
+		result["properties"] = json!({ "notes": record.notes });
+	}
+
+	// Правки применимы только к файлу с известным URI
+	let fixes: Vec<Value> = record
+		.suggestions
+		.iter()
+		.filter_map(|suggestion| Some((suggestion, suggestion.edit.as_ref()?, record.uri.as_ref()?)))
+		.map(|(suggestion, edit, uri)| {
+			json!({
+				"description": { "text": suggestion.message },
+				"artifactChanges": [{
+					"artifactLocation": { "uri": uri },
+					"replacements": [{
+						"deletedRegion": { "byteOffset": edit.start.offset, "byteLength": edit.end.offset.saturating_sub(edit.start.offset) },
+						"insertedContent": { "text": edit.replacement },
+					}],
+				}],
+			})
+		})
+		.collect();
+	if !fixes.is_empty() {
+		result["fixes"] = json!(fixes);
+	}
+
+	result
+}
+
+fn sarif_location(uri: Option<&str>, span: Option<(Position, Position)>) -> Value {
+	let mut location = json!({});
+	if let Some(uri) = uri {
+		location["artifactLocation"] = json!({ "uri": uri });
+	}
+	if let Some((start, end)) = span {
+		location["region"] = json!({
+			"startLine": start.line,
+			"startColumn": start.column,
+			"endLine": end.line,
+			"endColumn": end.column.max(start.column + usize::from(end == start)),
+			"byteOffset": start.offset,
+			"byteLength": end.offset.saturating_sub(start.offset),
+		});
+	}
+	location
+}
+
+/// `Diagnostic` из LSP: строки и символы с нуля, вторичные подписи — в `relatedInformation`, заметки — в конце сообщения.
+/// Колонки считаются в символах, как в `Position`; клиенту с UTF-16 нужен пересчёт
+pub fn lsp_diagnostic(record: &DiagnosticRecord) -> Value {
+	let range = |start: Position, end: Position| {
+		json!({
+			"start": { "line": start.line.saturating_sub(1), "character": start.column.saturating_sub(1) },
+			"end": { "line": end.line.saturating_sub(1), "character": end.column.saturating_sub(1) },
+		})
+	};
+	let start = record.start.unwrap_or_default();
+
+	let mut message = record.message.clone();
+	if let Some(label) = &record.label {
+		message = format!("{}: {}", message, label);
+	}
+	for note in &record.notes {
+		message = format!("{}\nnote: {}", message, note);
+	}
+
+	let mut diagnostic = json!({
+		"range": range(start, record.end.unwrap_or(start)),
+		"severity": match record.severity {
+			Severity::Error => 1,
+			Severity::Warning => 2,
+			Severity::Info => 3,
+			Severity::Hint => 4,
+		},
+		"code": record.code,
+		"source": "lacon",
+		"message": message,
+	});
+
+	// Подписи без URI клиенту не показать: у них нет документа
+	let related: Vec<Value> = record
+		.related
+		.iter()
+		.filter_map(|related| {
+			Some(json!({
+				"location": { "uri": related.uri.as_ref()?, "range": range(related.start, related.end) },
+				"message": related.message,
+			}))
+		})
+		.collect();
+	if !related.is_empty() {
+		diagnostic["relatedInformation"] = json!(related);
+	}
+
+	diagnostic
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::frontend::lexer::{check_unit_suffixes, scanned};
+	use crate::shared::{ErrorFlag, ErrorKind, ErrorReporter, ErrorReporterFlag, ErrorStorage, LexicalError, SemanticError, UnitError};
+	use std::path::PathBuf;
+
+	fn report(name: &str, reporter: fn(PathBuf) -> ErrorReporter) -> String {
+		let mut store = ErrorStorage::new();
+		store.add(Error::span(ErrorKind::Lexical(LexicalError::UnterminatedString), Position::new(1, 9, 8), Position::new(1, 14, 13)), ErrorFlag::Critical);
+		store.add(Error::at(ErrorKind::Semantic(SemanticError::Unit(UnitError::UnknownUnit("tick".into()))), Position::new(2, 3, 16)), ErrorFlag::Common);
+		let source = SourceFile::as_virtual("memory.lacon", "const a = 'abc\nx 3 tick\n");
+
+		let path = std::env::temp_dir().join(format!("lacon-{}-{}", std::process::id(), name));
+		reporter(path.clone()).report_all(&store, Some(&source)).unwrap();
+		let text = std::fs::read_to_string(&path).unwrap();
+		std::fs::remove_file(path).unwrap();
+		text
+	}
+
+	#[test]
+	fn json_lines_report() {
+		let lines = report("errors.jsonl", |path| ErrorReporter::JsonLines(path, ErrorReporterFlag::Truncate));
+		let first: Value = serde_json::from_str(lines.lines().next().unwrap()).unwrap();
+		assert_eq!(lines.lines().count(), 2);
+		assert_eq!((first["code"].as_str(), first["uri"].as_str(), first["end"]["offset"].as_u64()), (Some("L0003"), Some("memory.lacon"), Some(13)));
+	}
+
+	#[test]
+	fn sarif_report() {
+		let sarif: Value = serde_json::from_str(&report("errors.sarif", ErrorReporter::Sarif)).unwrap();
+		let run = &sarif["runs"][0];
+		assert_eq!(sarif["version"], "2.1.0");
+		assert_eq!(run["tool"]["driver"]["rules"].as_array().map(Vec::len), Some(2));
+		assert_eq!(run["results"][0]["locations"][0]["physicalLocation"]["region"]["byteLength"], 5);
+		assert_eq!(run["results"][1]["ruleId"], "U0003");
+	}
+
+	#[test]
+	fn scanner_offsets_are_bytes() {
+		let text = "// ёж\nlet a = 10 kgs\n";
+		let source = SourceFile::as_virtual("offsets.lacon", text);
+		let records: Vec<_> = scanned(text.as_bytes(), |tokens, ctx, errors| {
+			check_unit_suffixes(tokens, ctx, errors);
+			errors.all().iter().map(|stored| DiagnosticRecord::from_stored(stored, Some(&source), Locale::En)).collect()
+		});
+
+		let typo = text.find("kgs").unwrap();
+		let sarif = sarif_log(&records);
+		let result = &sarif["runs"][0]["results"][0];
+		assert_eq!(result["locations"][0]["physicalLocation"]["region"]["byteOffset"], typo);
+		assert_eq!(result["locations"][0]["physicalLocation"]["region"]["byteLength"], 3);
+		assert_eq!(result["fixes"][0]["artifactChanges"][0]["replacements"][0]["deletedRegion"]["byteOffset"], typo);
+
+		let line: Value = serde_json::from_str(json_lines(&records).trim_end()).unwrap();
+		assert_eq!((line["start"]["offset"].as_u64(), line["end"]["offset"].as_u64()), (Some(typo as u64), Some(typo as u64 + 3)));
+	}
+}
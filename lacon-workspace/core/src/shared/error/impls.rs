@@ -1,54 +1,83 @@
-use super::{Error, ErrorKind, ErrorPos, ErrorReporter, ErrorReporterFlag, Position};
+use super::{Diagnostic, DiagnosticRecord, Error, ErrorKind, ErrorPos, ErrorReporter, ErrorReporterFlag, ErrorStorage, Label, Locale, Position, Severity, report_to};
+use crate::shared::{SourceFile, SourceId};
 use std::fmt;
+use std::io;
 
 impl Error {
 	pub fn global(error_kind: ErrorKind) -> Self {
-		Self { kind: error_kind, pos: None }
+		Self::new(error_kind, None)
 	}
 	pub fn at(error_kind: ErrorKind, start: Position) -> Self {
-		Self {
-			kind: error_kind,
-			pos: Some(ErrorPos { start, end: None }),
-		}
+		Self::new(error_kind, Some(ErrorPos { start, end: None }))
 	}
 	pub fn span(kind: ErrorKind, start: Position, end: Position) -> Self {
+		Self::new(kind, Some(ErrorPos { start, end: Some(end) }))
+	}
+
+	fn new(kind: ErrorKind, pos: Option<ErrorPos>) -> Self {
 		Self {
 			kind,
-			pos: Some(ErrorPos { start, end: Some(end) }),
+			pos,
+			label: None,
+			secondary: Vec::new(),
+			notes: Vec::new(),
+			suggestions: Vec::new(),
 		}
 	}
+
+	pub fn with_label(mut self, message: impl Into<String>) -> Self {
+		self.label = Some(message.into());
+		self
+	}
+
+	/// Второе место в том же файле: `declared here`
+	pub fn with_secondary(mut self, start: Position, end: Option<Position>, message: impl Into<String>) -> Self {
+		self.secondary.push(Label {
+			source: None,
+			pos: ErrorPos { start, end },
+			message: message.into(),
+		});
+		self
+	}
+
+	pub fn with_secondary_in(mut self, source: SourceId, start: Position, end: Option<Position>, message: impl Into<String>) -> Self {
+		self.secondary.push(Label {
+			source: Some(source),
+			pos: ErrorPos { start, end },
+			message: message.into(),
+		});
+		self
+	}
+
+	pub fn with_note(mut self, message: impl Into<String>) -> Self {
+		self.notes.push(message.into());
+		self
+	}
 }
 
 impl ErrorReporter {
-	pub fn report(&self, error: &Error) {
-		use ErrorReporter::*;
+	pub fn report(&self, error: &Error) -> io::Result<()> {
+		let locale = self.locale();
+		self.report_diagnostics([Diagnostic::new(DiagnosticRecord::localized(error, Severity::Error, None, locale), error.message(locale))])
+	}
+
+	/// Всё хранилище сразу: SARIF — один документ на все ошибки, JSON и SARIF получают URI файла
+	pub fn report_all(&self, errors: &ErrorStorage, source: Option<&SourceFile>) -> io::Result<()> {
+		let locale = self.locale();
+		self.report_diagnostics(errors.all().iter().map(|stored| Diagnostic::new(DiagnosticRecord::from_stored(stored, source, locale), stored.error.message(locale))))
+	}
+
+	/// Язык сообщений: заданный через `Localized` или из окружения
+	pub fn locale(&self) -> Locale {
 		match self {
-			Silent => {}
-			Console => println!("{}", error),
-			File(path, flag) => {
-				use std::fs::OpenOptions;
-				use std::io::Write;
-
-				let flag = *flag;
-
-				let mut file = OpenOptions::new()
-					.create(true)
-					.write(true)
-					.truncate(flag == ErrorReporterFlag::Truncate)
-					.append(flag == ErrorReporterFlag::Append)
-					.open(path)
-					.expect("Cannot open file to write error");
-
-				writeln!(file, "{}", error).expect("Cannot write error to file");
-			}
-			Log(path, flag) => {
-				let flag = *flag;
-
-				Console.report(error);
-				File(path.clone(), flag).report(error);
-			}
+			ErrorReporter::Localized(locale, _) => *locale,
+			_ => Locale::from_env(),
 		}
 	}
+
+	pub fn report_diagnostics(&self, diagnostics: impl IntoIterator<Item = Diagnostic>) -> io::Result<()> {
+		report_to(self.sink()?.as_mut(), diagnostics)
+	}
 }
 
 // Defaults
@@ -79,6 +108,17 @@ impl fmt::Display for ErrorPos {
 	}
 }
 
+impl fmt::Display for Severity {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Severity::Hint => "hint",
+			Severity::Info => "info",
+			Severity::Warning => "warning",
+			Severity::Error => "error",
+		})
+	}
+}
+
 impl fmt::Display for Error {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		write!(f, "[{}]", self.kind)?;
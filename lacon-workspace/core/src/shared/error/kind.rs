@@ -1,20 +1,28 @@
+use strum_macros::EnumIter;
 use thiserror::Error;
 
-#[derive(Debug, Clone, Error)]
+// `iter()` даёт только варианты без вложенных ошибок: вложенные перечисляются своими итераторами
+#[derive(Debug, Clone, Error, EnumIter)]
 pub enum ErrorKind {
 	#[error("Unknown error")]
 	Unknown,
+	#[strum(disabled)]
 	#[error("Lexical error: {0}")]
 	Lexical(LexicalError),
+	#[strum(disabled)]
 	#[error("Syntax error: {0}")]
 	Syntax(SyntaxError),
+	#[strum(disabled)]
 	#[error("Semantic error: {0}")]
 	Semantic(SemanticError),
+	#[strum(disabled)]
+	#[error("Lint: {0}")]
+	Lint(LintError),
 	#[error("Runtime error")]
 	Runtime,
 }
 
-#[derive(Debug, Clone, Error)]
+#[derive(Debug, Clone, Error, EnumIter)]
 pub enum LexicalError {
 	#[error("Invalid character: {0}")]
 	InvalidCharacter(char),
@@ -32,10 +40,12 @@ pub enum LexicalError {
 	InvalidIndentation,
 }
 
-#[derive(Debug, Clone, Error)]
+#[derive(Debug, Clone, Error, EnumIter)]
 pub enum SyntaxError {
 	#[error("Expected {expected}, found {found}")]
 	Expected { expected: &'static str, found: &'static str },
+	#[error("Unknown keyword `{0}`")]
+	UnknownKeyword(String),
 	// UnexpectedToken,
 	// MissingToken,
 	// InvalidExpression,
@@ -50,11 +60,27 @@ pub enum SyntaxError {
 	// InvalidControlFlow,
 }
 
-#[derive(Debug, Clone, Error)]
+#[derive(Debug, Clone, Error, EnumIter)]
+pub enum LintError {
+	#[error("Suppression of `{0}` does not suppress anything")]
+	UnusedSuppression(String),
+	#[error("Unknown diagnostic code `{0}`")]
+	UnknownCode(String),
+	#[error("Invalid lint level `{0}`, expected `allow`, `warn` or `deny`")]
+	InvalidLevel(String),
+	#[error("Invalid suppression directive: {0}")]
+	InvalidDirective(&'static str),
+}
+
+#[derive(Debug, Clone, Error, EnumIter)]
 pub enum SemanticError {
 	#[error("Expected type {expected}, found {found}")]
 	TypeMismatch { expected: String, found: String },
-	// UndefinedVariable,
+	#[strum(disabled)]
+	#[error("{0}")]
+	Unit(UnitError),
+	#[error("Undefined name `{0}`")]
+	UndefinedName(String),
 	// Redefinition,
 	// InvalidOperation,
 	// ConstAssignment,
@@ -69,3 +95,39 @@ pub enum SemanticError {
 	// InvalidMethodAccess,
 	// InvalidOperator,
 }
+
+#[derive(Debug, Clone, Error, EnumIter)]
+pub enum UnitError {
+	#[error("Unit `{0}` is already declared")]
+	DuplicateSymbol(String),
+	#[error("Invalid unit symbol `{0}`")]
+	InvalidSymbol(String),
+	#[error("Unknown unit `{0}`")]
+	UnknownUnit(String),
+	#[error("Too many custom dimensions (limit is {0})")]
+	TooManyDimensions(usize),
+	#[error("Invalid unit declaration: {0}")]
+	InvalidDeclaration(&'static str),
+	#[error("Cannot convert `{0}` to `{1}`")]
+	IncompatibleUnits(String, String),
+	#[error("`{0}` depends on layout context and cannot be converted yet")]
+	Deferred(String),
+	#[error("Cannot convert `{0}` to `{1}`: calendar durations have no fixed length")]
+	AmbiguousCalendar(String, String),
+	#[error("`{0}` is outside the `{1}` unit profile")]
+	OutsideProfile(String, String),
+	#[error("`{0}` is outside the `{1}` unit profile, use `{2}`")]
+	OutsideProfileConvert(String, String, String),
+	#[error("Unknown unit profile `{0}`")]
+	UnknownProfile(String),
+	#[error("Invalid rate signature `{0}`, expected `<Quantity> ÷ delta <Quantity>`")]
+	InvalidRateSignature(String),
+	#[error("`{0}` cannot be used as an interval")]
+	InvalidInterval(String),
+	#[error("Rate `{0}` does not match `{1}`")]
+	RateMismatch(String, String),
+	#[error("Unit declarations from `{0}` are not available")]
+	UnknownUnitImport(String),
+	#[error("`{0}` cannot be evaluated as a quantity")]
+	UnreadableQuantity(String),
+}
@@ -0,0 +1,299 @@
+use super::{Error, ErrorKind, ErrorPos, LexicalError, LintError, SemanticError, SyntaxError, UnitError};
+use strum_macros::EnumString;
+
+/// Язык сообщений диагностик. Коды (`U0003`) и метки (`error`, `help`) не переводятся
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum Locale {
+	#[default]
+	En,
+	Ru,
+}
+
+const LOCALE_VARS: [&str; 4] = ["LACON_LANG", "LC_ALL", "LC_MESSAGES", "LANG"];
+
+impl Locale {
+	/// Первая непустая из `LACON_LANG`, `LC_ALL`, `LC_MESSAGES`, `LANG`; неизвестный язык и `C` — английский
+	pub fn from_env() -> Self {
+		Self::from_vars(|name| std::env::var(name).ok())
+	}
+
+	pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Self {
+		LOCALE_VARS.iter().filter_map(|name| var(name)).find(|value| !value.is_empty()).and_then(|tag| Self::from_tag(&tag)).unwrap_or_default()
+	}
+
+	/// `ru`, `ru_RU.UTF-8`, `ru-RU` -> `Ru`
+	pub fn from_tag(tag: &str) -> Option<Self> {
+		tag.split(['_', '-', '.', '@']).next()?.to_ascii_lowercase().parse().ok()
+	}
+}
+
+// Ключ — код диагностики или категория; пустой перевод — его нет, берётся английский.
+// Аргументы подставляются один раз: `{` и `}` внутри значений не раскрываются повторно
+pub static MESSAGES: &[(&str, &str, &str)] = &[
+	("lexical", "Lexical error: {message}", "Лексическая ошибка: {message}"),
+	("syntax", "Syntax error: {message}", "Синтаксическая ошибка: {message}"),
+	("semantic", "Semantic error: {message}", "Семантическая ошибка: {message}"),
+	("lint", "Lint: {message}", "Линт: {message}"),
+	("position", "on position at ({start})", "в позиции ({start})"),
+	("range", "on position at ({start}) to ({end})", "в позиции с ({start}) по ({end})"),
+	("E0000", "Unknown error", "Неизвестная ошибка"),
+	("R0001", "Runtime error", "Ошибка выполнения"),
+	("L0001", "Invalid character: {char}", "Недопустимый символ: {char}"),
+	("L0002", "Invalid token", "Недопустимый токен"),
+	("L0003", "Unterminated string literal", "Незакрытая строка"),
+	("L0004", "Unterminated block comment", "Незакрытый блочный комментарий"),
+	("L0005", "Invalid escape sequence: {sequence}", "Недопустимая escape-последовательность: {sequence}"),
+	("L0006", "Invalid indentation", "Недопустимый отступ"),
+	("L0007", "Invalid indentation", "Недопустимый отступ"),
+	("S0001", "Expected {expected}, found {found}", "Ожидалось {expected}, найдено {found}"),
+	("S0002", "Unknown keyword `{word}`", "Неизвестное ключевое слово `{word}`"),
+	("T0001", "Expected type {expected}, found {found}", "Ожидался тип {expected}, найден {found}"),
+	("T0002", "Undefined name `{name}`", "Имя `{name}` не объявлено"),
+	("U0001", "Unit `{unit}` is already declared", "Юнит `{unit}` уже объявлен"),
+	("U0002", "Invalid unit symbol `{unit}`", "Недопустимый символ юнита `{unit}`"),
+	("U0003", "Unknown unit `{unit}`", "Неизвестный юнит `{unit}`"),
+	("U0004", "Too many custom dimensions (limit is {limit})", "Слишком много пользовательских размерностей (предел — {limit})"),
+	("U0005", "Invalid unit declaration: {reason}", "Неверное объявление юнита: {reason}"),
+	("U0006", "Cannot convert `{from}` to `{to}`", "Нельзя перевести `{from}` в `{to}`"),
+	("U0007", "`{unit}` depends on layout context and cannot be converted yet", "`{unit}` зависит от раскладки и пока не переводится"),
+	(
+		"U0008",
+		"Cannot convert `{from}` to `{to}`: calendar durations have no fixed length",
+		"Нельзя перевести `{from}` в `{to}`: у календарных периодов нет постоянной длины",
+	),
+	("U0009", "`{unit}` is outside the `{profile}` unit profile", "`{unit}` не входит в профиль юнитов `{profile}`"),
+	(
+		"U0009.convert",
+		"`{unit}` is outside the `{profile}` unit profile, use `{suggested}`",
+		"`{unit}` не входит в профиль юнитов `{profile}`, используйте `{suggested}`",
+	),
+	("U0010", "Unknown unit profile `{profile}`", "Неизвестный профиль юнитов `{profile}`"),
+	(
+		"U0011",
+		"Invalid rate signature `{signature}`, expected `<Quantity> ÷ delta <Quantity>`",
+		"Неверная сигнатура скорости `{signature}`, ожидалось `<Quantity> ÷ delta <Quantity>`",
+	),
+	("U0012", "`{interval}` cannot be used as an interval", "`{interval}` нельзя использовать как интервал"),
+	("U0013", "Rate `{rate}` does not match `{signature}`", "Скорость `{rate}` не соответствует `{signature}`"),
+	("U0014", "Unit declarations from `{path}` are not available", "Объявления юнитов из `{path}` недоступны"),
+	("U0015", "`{quantity}` cannot be evaluated as a quantity", "`{quantity}` не вычисляется как величина"),
+	("W0001", "Suppression of `{code}` does not suppress anything", "Подавление `{code}` ничего не подавляет"),
+	("W0002", "Unknown diagnostic code `{code}`", "Неизвестный код диагностики `{code}`"),
+	("W0003", "Invalid lint level `{level}`, expected `allow`, `warn` or `deny`", "Неверный уровень линта `{level}`, ожидалось `allow`, `warn` или `deny`"),
+	("W0004", "Invalid suppression directive: {reason}", "Неверная директива подавления: {reason}"),
+	("did_you_mean", "did you mean `{replacement}`?", "возможно, имелось в виду `{replacement}`?"),
+	("more.one", "... and 1 more error", "... и ещё одна ошибка"),
+	("more", "... and {count} more errors", "... скрыто ошибок: {count}"),
+];
+
+// Статические пояснения внутри сообщений: причины в `InvalidDeclaration` и `InvalidDirective`
+pub static PHRASES: &[(&str, &str)] = &[
+	("expected `:` or `=`", "ожидалось `:` или `=`"),
+	("expected base unit", "ожидался базовый юнит"),
+	("expected dimension name", "ожидалось имя размерности"),
+	("expected import path", "ожидался путь импорта"),
+	("expected offset", "ожидалось смещение"),
+	("expected prefix group", "ожидалась группа префиксов"),
+	("expected scale", "ожидался множитель"),
+	("expected unit profile name", "ожидалось имя профиля юнитов"),
+	("expected unit symbol", "ожидался символ юнита"),
+	("unexpected token", "неожиданный токен"),
+	("unit registry is full", "реестр юнитов заполнен"),
+	("unknown prefix group", "неизвестная группа префиксов"),
+	("`lacon-allow-begin` is never closed by `lacon-allow-end`", "`lacon-allow-begin` не закрыт `lacon-allow-end`"),
+	("`lacon-allow-end` without `lacon-allow-begin`", "`lacon-allow-end` без `lacon-allow-begin`"),
+	("expected `next-line`, `begin`, `end` or `file`", "ожидалось `next-line`, `begin`, `end` или `file`"),
+	("expected at least one diagnostic code", "ожидался хотя бы один код диагностики"),
+];
+
+/// Шаблон по ключу с откатом на английский; `None`, если ключа нет в каталоге
+pub fn message_template(key: &str, locale: Locale) -> Option<&'static str> {
+	let (_, en, ru) = MESSAGES.iter().find(|(k, ..)| *k == key)?;
+	Some(match locale {
+		Locale::Ru if !ru.is_empty() => ru,
+		_ => en,
+	})
+}
+
+/// `{name}` заменяется значением аргумента; неизвестные плейсхолдеры и одиночные скобки остаются как есть
+pub fn interpolate(template: &str, args: &[(&str, String)]) -> String {
+	let mut out = String::with_capacity(template.len());
+	let mut rest = template;
+
+	while let Some(open) = rest.find('{') {
+		out.push_str(&rest[..open]);
+		let after = &rest[open + 1..];
+		match after.find('}').and_then(|close| Some((args.iter().find(|(name, _)| *name == &after[..close])?, close))) {
+			Some(((_, value), close)) => {
+				out.push_str(value);
+				rest = &after[close + 1..];
+			}
+			None => {
+				out.push('{');
+				rest = after;
+			}
+		}
+	}
+
+	out.push_str(rest);
+	out
+}
+
+pub fn phrase(text: &'static str, locale: Locale) -> &'static str {
+	match locale {
+		Locale::En => text,
+		Locale::Ru => PHRASES.iter().find(|(en, _)| *en == text).map_or(text, |(_, ru)| ru),
+	}
+}
+
+/// Сообщение каталога с аргументами; ключ без перевода выводится как есть
+pub fn localize(key: &str, args: &[(&str, String)], locale: Locale) -> String {
+	interpolate(message_template(key, locale).unwrap_or(key), args)
+}
+
+impl Error {
+	/// Сообщение с позицией, как `to_string`, но на выбранном языке
+	pub fn message(&self, locale: Locale) -> String {
+		match &self.pos {
+			Some(pos) => format!("[{}] {}", self.kind.message(locale), pos.message(locale)),
+			None => format!("[{}]", self.kind.message(locale)),
+		}
+	}
+}
+
+impl ErrorPos {
+	pub fn message(&self, locale: Locale) -> String {
+		match self.end {
+			Some(end) => localize("range", &[("start", self.start.to_string()), ("end", end.to_string())], locale),
+			None => localize("position", &[("start", self.start.to_string())], locale),
+		}
+	}
+}
+
+impl ErrorKind {
+	pub fn message(&self, locale: Locale) -> String {
+		let (category, message) = match self {
+			ErrorKind::Lexical(error) => ("lexical", error.message(locale)),
+			ErrorKind::Syntax(error) => ("syntax", error.message(locale)),
+			ErrorKind::Semantic(error) => ("semantic", error.message(locale)),
+			ErrorKind::Lint(error) => ("lint", error.message(locale)),
+			ErrorKind::Unknown | ErrorKind::Runtime => return localize(self.code(), &[], locale),
+		};
+		localize(category, &[("message", message)], locale)
+	}
+}
+
+impl LexicalError {
+	pub fn message(&self, locale: Locale) -> String {
+		let args = match self {
+			LexicalError::InvalidCharacter(c) => vec![("char", c.to_string())],
+			LexicalError::InvalidEscapeSequence(sequence) => vec![("sequence", sequence.clone())],
+			_ => Vec::new(),
+		};
+		localize(self.code(), &args, locale)
+	}
+}
+
+impl SyntaxError {
+	pub fn message(&self, locale: Locale) -> String {
+		let args = match self {
+			SyntaxError::Expected { expected, found } => vec![("expected", expected.to_string()), ("found", found.to_string())],
+			SyntaxError::UnknownKeyword(word) => vec![("word", word.clone())],
+		};
+		localize(self.code(), &args, locale)
+	}
+}
+
+impl SemanticError {
+	pub fn message(&self, locale: Locale) -> String {
+		let args = match self {
+			SemanticError::TypeMismatch { expected, found } => vec![("expected", expected.clone()), ("found", found.clone())],
+			SemanticError::Unit(error) => return error.message(locale),
+			SemanticError::UndefinedName(name) => vec![("name", name.clone())],
+		};
+		localize(self.code(), &args, locale)
+	}
+}
+
+impl LintError {
+	pub fn message(&self, locale: Locale) -> String {
+		let args = match self {
+			LintError::UnusedSuppression(code) | LintError::UnknownCode(code) => vec![("code", code.clone())],
+			LintError::InvalidLevel(level) => vec![("level", level.clone())],
+			LintError::InvalidDirective(reason) => vec![("reason", phrase(reason, locale).to_string())],
+		};
+		localize(self.code(), &args, locale)
+	}
+}
+
+impl UnitError {
+	pub fn message(&self, locale: Locale) -> String {
+		use UnitError::*;
+
+		let unit = |unit: &String| vec![("unit", unit.clone())];
+		let pair = |from: &String, to: &String| vec![("from", from.clone()), ("to", to.clone())];
+		let (key, args) = match self {
+			DuplicateSymbol(symbol) | InvalidSymbol(symbol) | UnknownUnit(symbol) | Deferred(symbol) => (self.code(), unit(symbol)),
+			TooManyDimensions(limit) => (self.code(), vec![("limit", limit.to_string())]),
+			InvalidDeclaration(reason) => (self.code(), vec![("reason", phrase(reason, locale).to_string())]),
+			IncompatibleUnits(from, to) | AmbiguousCalendar(from, to) => (self.code(), pair(from, to)),
+			OutsideProfile(symbol, profile) => (self.code(), vec![("unit", symbol.clone()), ("profile", profile.clone())]),
+			OutsideProfileConvert(symbol, profile, suggested) => ("U0009.convert", vec![("unit", symbol.clone()), ("profile", profile.clone()), ("suggested", suggested.clone())]),
+			UnknownProfile(profile) => (self.code(), vec![("profile", profile.clone())]),
+			InvalidRateSignature(signature) => (self.code(), vec![("signature", signature.clone())]),
+			InvalidInterval(interval) => (self.code(), vec![("interval", interval.clone())]),
+			RateMismatch(rate, signature) => (self.code(), vec![("rate", rate.clone()), ("signature", signature.clone())]),
+			UnknownUnitImport(path) => (self.code(), vec![("path", path.clone())]),
+			UnreadableQuantity(quantity) => (self.code(), vec![("quantity", quantity.clone())]),
+		};
+		localize(key, &args, locale)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::shared::{ErrorReporter, Position};
+
+	fn outside_profile() -> ErrorKind {
+		ErrorKind::Semantic(SemanticError::Unit(UnitError::OutsideProfileConvert("5ft".into(), "SI".into(), "1.524m".into())))
+	}
+
+	#[test]
+	fn english_catalog_matches_display() {
+		let kinds = [
+			ErrorKind::Lexical(LexicalError::InvalidCharacter('¤')),
+			ErrorKind::Syntax(SyntaxError::Expected { expected: "Function", found: "Variable" }),
+			outside_profile(),
+			ErrorKind::Semantic(SemanticError::Unit(UnitError::InvalidDeclaration("expected scale"))),
+			ErrorKind::Lint(LintError::InvalidDirective("expected at least one diagnostic code")),
+			ErrorKind::Runtime,
+		];
+		for kind in &kinds {
+			assert_eq!(kind.message(Locale::En), kind.to_string());
+		}
+	}
+
+	#[test]
+	fn russian_catalog() {
+		assert_eq!(outside_profile().message(Locale::Ru), "Семантическая ошибка: `5ft` не входит в профиль юнитов `SI`, используйте `1.524m`");
+		let declaration = ErrorKind::Semantic(SemanticError::Unit(UnitError::InvalidDeclaration("expected scale")));
+		assert_eq!(declaration.message(Locale::Ru), "Семантическая ошибка: Неверное объявление юнита: ожидался множитель");
+	}
+
+	#[test]
+	fn arguments_are_not_expanded_twice() {
+		let error = Error::at(ErrorKind::Semantic(SemanticError::UndefinedName("{name}".into())), Position::new(2, 3, 14));
+		assert_eq!(error.message(Locale::Ru), "[Семантическая ошибка: Имя `{name}` не объявлено] в позиции (2:3:14)");
+		assert_eq!(interpolate("{a} {b} {", &[("a", "{b}".into())]), "{b} {b} {");
+	}
+
+	#[test]
+	fn locale_from_environment() {
+		let env = |lang: &'static str| move |name: &str| (name == "LANG").then(|| lang.to_string());
+		assert_eq!(Locale::from_vars(env("ru_RU.UTF-8")), Locale::Ru);
+		assert_eq!(Locale::from_vars(env("C")), Locale::En);
+		assert_eq!(ErrorReporter::Localized(Locale::Ru, Box::new(ErrorReporter::Silent)).locale(), Locale::Ru);
+	}
+}
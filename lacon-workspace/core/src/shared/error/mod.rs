@@ -1,11 +1,23 @@
+mod codes;
 mod enums;
+mod formats;
 mod impls;
 mod kind;
+mod messages;
+mod sinks;
+mod store;
 mod structs;
+mod suggestions;
 
+pub use codes::*;
 pub use enums::*;
+pub use formats::*;
 pub use kind::*;
+pub use messages::*;
+pub use sinks::*;
+pub use store::*;
 pub use structs::*;
+pub use suggestions::*;
 
 use crate::shared::position::Position;
 
@@ -30,14 +42,14 @@ mod errtest {
 		store.add(l_err, ErrorFlag::Critical);
 
 		for err in store.all() {
-			ErrorReporter::Console.report(&err.error);
+			ErrorReporter::Console.report(&err.error).expect("console is writable");
 		}
 
 		println!("\n\n------------------\n\n");
 
 		let lexical_errors = store.filter_by_kind(|k| matches!(k, ErrorKind::Lexical(_)));
 		for err in lexical_errors {
-			ErrorReporter::Console.report(&err.error);
+			ErrorReporter::Console.report(&err.error).expect("console is writable");
 		}
 	}
 }
@@ -0,0 +1,364 @@
+use super::{DiagnosticRecord, ErrorReporter, ErrorReporterFlag, Locale, json_lines, localize, sarif_log};
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Диагностика, готовая к выводу: запись для машинных форматов и текст для людей
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+	pub record: DiagnosticRecord,
+	/// Текст для файлов и логов
+	pub plain: String,
+	/// Текст для терминала, может быть с цветом; `None` — как `plain`
+	pub console: Option<String>,
+}
+
+impl Diagnostic {
+	pub fn new(record: DiagnosticRecord, plain: String) -> Self {
+		Self { record, plain, console: None }
+	}
+
+	pub fn with_console(mut self, console: String) -> Self {
+		self.console = Some(console);
+		self
+	}
+
+	pub fn console_text(&self) -> &str {
+		self.console.as_deref().unwrap_or(&self.plain)
+	}
+}
+
+/// Приёмник диагностик. Встроенные — консоль, файл, JSON Lines и SARIF; хост может подключить свой (LSP, окно редактора)
+pub trait DiagnosticSink {
+	fn emit(&mut self, diagnostic: &Diagnostic) -> io::Result<()>;
+
+	/// Служебная строка без диагностики, например `... and 3 more errors`; машинные форматы её пропускают
+	fn note(&mut self, _text: &str) -> io::Result<()> {
+		Ok(())
+	}
+
+	/// После последней диагностики: SARIF пишет документ, файлы сбрасывают буферы
+	fn finish(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+/// Выводит все диагностики и завершает приёмник; первая ошибка ввода-вывода прерывает вывод
+pub fn report_to(sink: &mut dyn DiagnosticSink, diagnostics: impl IntoIterator<Item = Diagnostic>) -> io::Result<()> {
+	for diagnostic in diagnostics {
+		sink.emit(&diagnostic)?;
+	}
+	sink.finish()
+}
+
+pub struct ConsoleSink;
+
+impl DiagnosticSink for ConsoleSink {
+	fn emit(&mut self, diagnostic: &Diagnostic) -> io::Result<()> {
+		writeln!(io::stdout().lock(), "{}", diagnostic.console_text())
+	}
+
+	fn note(&mut self, text: &str) -> io::Result<()> {
+		writeln!(io::stdout().lock(), "{}", text)
+	}
+}
+
+pub struct FileSink {
+	file: File,
+}
+
+impl FileSink {
+	pub fn open(path: &Path, flag: ErrorReporterFlag) -> io::Result<Self> {
+		open_file(path, flag).map(|file| Self { file })
+	}
+}
+
+impl DiagnosticSink for FileSink {
+	fn emit(&mut self, diagnostic: &Diagnostic) -> io::Result<()> {
+		writeln!(self.file, "{}", diagnostic.plain)
+	}
+
+	fn note(&mut self, text: &str) -> io::Result<()> {
+		writeln!(self.file, "{}", text)
+	}
+
+	fn finish(&mut self) -> io::Result<()> {
+		self.file.flush()
+	}
+}
+
+pub struct JsonLinesSink {
+	file: File,
+}
+
+impl JsonLinesSink {
+	pub fn open(path: &Path, flag: ErrorReporterFlag) -> io::Result<Self> {
+		open_file(path, flag).map(|file| Self { file })
+	}
+}
+
+impl DiagnosticSink for JsonLinesSink {
+	fn emit(&mut self, diagnostic: &Diagnostic) -> io::Result<()> {
+		self.file.write_all(json_lines(std::slice::from_ref(&diagnostic.record)).as_bytes())
+	}
+
+	fn finish(&mut self) -> io::Result<()> {
+		self.file.flush()
+	}
+}
+
+/// SARIF — один документ на весь запуск, поэтому записи копятся до `finish`
+pub struct SarifSink {
+	path: PathBuf,
+	records: Vec<DiagnosticRecord>,
+}
+
+impl SarifSink {
+	pub fn new(path: PathBuf) -> Self {
+		Self { path, records: Vec::new() }
+	}
+}
+
+impl DiagnosticSink for SarifSink {
+	fn emit(&mut self, diagnostic: &Diagnostic) -> io::Result<()> {
+		self.records.push(diagnostic.record.clone());
+		Ok(())
+	}
+
+	fn finish(&mut self) -> io::Result<()> {
+		std::fs::write(&self.path, format!("{:#}\n", sarif_log(&self.records)))
+	}
+}
+
+/// Сбор записей в память — для тестов и хостов, которые показывают диагностики сами
+impl DiagnosticSink for Vec<DiagnosticRecord> {
+	fn emit(&mut self, diagnostic: &Diagnostic) -> io::Result<()> {
+		self.push(diagnostic.record.clone());
+		Ok(())
+	}
+}
+
+/// Несколько приёмников сразу. Сбой одного не мешает остальным, возвращается первая ошибка.
+/// Пустой набор ничего не выводит
+#[derive(Default)]
+pub struct FanOut {
+	sinks: Vec<Box<dyn DiagnosticSink>>,
+}
+
+impl FanOut {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn with(mut self, sink: impl DiagnosticSink + 'static) -> Self {
+		self.sinks.push(Box::new(sink));
+		self
+	}
+
+	pub fn push(&mut self, sink: Box<dyn DiagnosticSink>) {
+		self.sinks.push(sink);
+	}
+
+	fn each(&mut self, mut call: impl FnMut(&mut dyn DiagnosticSink) -> io::Result<()>) -> io::Result<()> {
+		let mut result = Ok(());
+		for sink in &mut self.sinks {
+			let outcome = call(sink.as_mut());
+			if result.is_ok() {
+				result = outcome;
+			}
+		}
+		result
+	}
+}
+
+impl DiagnosticSink for FanOut {
+	fn emit(&mut self, diagnostic: &Diagnostic) -> io::Result<()> {
+		self.each(|sink| sink.emit(diagnostic))
+	}
+
+	fn note(&mut self, text: &str) -> io::Result<()> {
+		self.each(|sink| sink.note(text))
+	}
+
+	fn finish(&mut self) -> io::Result<()> {
+		self.each(|sink| sink.finish())
+	}
+}
+
+type DiagnosticKey = (&'static str, String, Option<usize>, Option<usize>, Option<String>);
+
+/// Ограничение и дедупликация поверх другого приёмника:
+/// одинаковые код, сообщение и диапазон выводятся один раз, сверх `max` — только итог `... and N more errors`
+pub struct Limited<S> {
+	inner: S,
+	max: Option<usize>,
+	dedup: bool,
+	locale: Locale,
+	seen: HashSet<DiagnosticKey>,
+	shown: usize,
+	hidden: usize,
+}
+
+impl<S: DiagnosticSink> Limited<S> {
+	pub fn new(inner: S) -> Self {
+		Self {
+			inner,
+			max: None,
+			dedup: false,
+			locale: Locale::from_env(),
+			seen: HashSet::new(),
+			shown: 0,
+			hidden: 0,
+		}
+	}
+
+	pub fn with_max(mut self, max: usize) -> Self {
+		self.max = Some(max);
+		self
+	}
+
+	pub fn deduplicate(mut self) -> Self {
+		self.dedup = true;
+		self
+	}
+
+	/// Язык итоговой строки; по умолчанию — из окружения, как у `ErrorReporter`
+	pub fn with_locale(mut self, locale: Locale) -> Self {
+		self.locale = locale;
+		self
+	}
+
+	pub fn hidden(&self) -> usize {
+		self.hidden
+	}
+
+	pub fn into_inner(self) -> S {
+		self.inner
+	}
+}
+
+impl<S: DiagnosticSink> DiagnosticSink for Limited<S> {
+	fn emit(&mut self, diagnostic: &Diagnostic) -> io::Result<()> {
+		let record = &diagnostic.record;
+		let key = (record.code, record.message.clone(), record.start.map(|pos| pos.offset), record.end.map(|pos| pos.offset), record.uri.clone());
+		if self.dedup && !self.seen.insert(key) {
+			return Ok(());
+		}
+		if self.max.is_some_and(|max| self.shown >= max) {
+			self.hidden += 1;
+			return Ok(());
+		}
+
+		self.shown += 1;
+		self.inner.emit(diagnostic)
+	}
+
+	fn note(&mut self, text: &str) -> io::Result<()> {
+		self.inner.note(text)
+	}
+
+	fn finish(&mut self) -> io::Result<()> {
+		if self.hidden > 0 {
+			let key = if self.hidden == 1 { "more.one" } else { "more" };
+			self.inner.note(&localize(key, &[("count", self.hidden.to_string())], self.locale))?;
+		}
+		self.inner.finish()
+	}
+}
+
+impl ErrorReporter {
+	/// Приёмник для этого репортёра; файлы открываются сразу, так что недоступный путь — ошибка здесь, а не паника при выводе
+	pub fn sink(&self) -> io::Result<Box<dyn DiagnosticSink>> {
+		Ok(match self {
+			ErrorReporter::Silent => Box::new(FanOut::new()),
+			ErrorReporter::Console => Box::new(ConsoleSink),
+			ErrorReporter::File(path, flag) => Box::new(FileSink::open(path, *flag)?),
+			ErrorReporter::Log(path, flag) => Box::new(FanOut::new().with(ConsoleSink).with(FileSink::open(path, *flag)?)),
+			ErrorReporter::JsonLines(path, flag) => Box::new(JsonLinesSink::open(path, *flag)?),
+			ErrorReporter::Sarif(path) => Box::new(SarifSink::new(path.clone())),
+			ErrorReporter::Localized(_, reporter) => reporter.sink()?,
+		})
+	}
+}
+
+fn open_file(path: &Path, flag: ErrorReporterFlag) -> io::Result<File> {
+	OpenOptions::new().create(true).write(true).truncate(flag == ErrorReporterFlag::Truncate).append(flag == ErrorReporterFlag::Append).open(path)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::shared::{Error, ErrorKind, Position, SemanticError, Severity, UnitError};
+	use std::cell::Cell;
+	use std::rc::Rc;
+
+	fn unknown(symbol: &str, offset: usize) -> Error {
+		Error::at(ErrorKind::Semantic(SemanticError::Unit(UnitError::UnknownUnit(symbol.into()))), Position::new(1, offset + 1, offset))
+	}
+
+	fn diagnostics() -> Vec<Diagnostic> {
+		let errors = [unknown("tick", 4), unknown("tick", 4), unknown("cell", 12), unknown("tile", 20), unknown("rack", 28)];
+		errors.iter().map(|error| Diagnostic::new(DiagnosticRecord::new(error, Severity::Error, None), error.to_string())).collect()
+	}
+
+	fn missing_path() -> PathBuf {
+		std::env::temp_dir().join(format!("lacon-missing-{}", std::process::id())).join("errors.log")
+	}
+
+	#[test]
+	fn limited_sink_deduplicates() {
+		let mut limited = Limited::new(Vec::new()).deduplicate().with_max(2);
+		report_to(&mut limited, diagnostics()).unwrap();
+		assert_eq!(limited.hidden(), 2);
+		assert_eq!(
+			limited.into_inner().iter().map(|record| record.message.as_str()).collect::<Vec<_>>(),
+			["Semantic error: Unknown unit `tick`", "Semantic error: Unknown unit `cell`"]
+		);
+	}
+
+	#[test]
+	fn limited_summary_follows_the_locale() {
+		#[derive(Default)]
+		struct Notes(Vec<String>);
+		impl DiagnosticSink for Notes {
+			fn emit(&mut self, _: &Diagnostic) -> io::Result<()> {
+				Ok(())
+			}
+
+			fn note(&mut self, text: &str) -> io::Result<()> {
+				self.0.push(text.to_string());
+				Ok(())
+			}
+		}
+
+		for (locale, summary) in [(Locale::En, "... and 2 more errors"), (Locale::Ru, "... скрыто ошибок: 2")] {
+			let mut limited = Limited::new(Notes::default()).deduplicate().with_max(2).with_locale(locale);
+			report_to(&mut limited, diagnostics()).unwrap();
+			assert_eq!(limited.into_inner().0, [summary]);
+		}
+	}
+
+	#[test]
+	fn unwritable_file_is_an_error() {
+		// Недоступный путь — ошибка, а не паника
+		assert!(ErrorReporter::File(missing_path(), ErrorReporterFlag::Append).report(&unknown("tick", 4)).is_err());
+	}
+
+	#[test]
+	fn fan_out_reaches_every_sink() {
+		struct Counter(Rc<Cell<usize>>);
+		impl DiagnosticSink for Counter {
+			fn emit(&mut self, _: &Diagnostic) -> io::Result<()> {
+				self.0.set(self.0.get() + 1);
+				Ok(())
+			}
+		}
+
+		// Ошибка одного приёмника не мешает остальным получить диагностику
+		let count = Rc::new(Cell::new(0));
+		let mut fan_out = FanOut::new().with(SarifSink::new(missing_path())).with(Counter(count.clone()));
+		assert!(report_to(&mut fan_out, diagnostics()).is_err());
+		assert_eq!(count.get(), 5);
+	}
+}
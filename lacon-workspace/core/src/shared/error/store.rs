@@ -0,0 +1,157 @@
+use super::{DiagnosticId, Error, ErrorFlag, ErrorStorage, Severity, StoredError};
+use crate::shared::SourceId;
+use std::collections::HashMap;
+use std::ops::Range;
+
+// Порядок внутри файла: сначала диагностики без позиции, затем по началу, концу и id
+type SortKey = (Option<usize>, Option<usize>, DiagnosticId);
+
+/// Диагностики проекта по файлам, для LSP и повторной проверки.
+/// Внутри файла отсортированы по позиции; при повторном анализе файл очищается целиком через `replace`
+#[derive(Debug, Default)]
+pub struct DiagnosticStore {
+	files: HashMap<SourceId, Vec<StoredError>>,
+	index: HashMap<DiagnosticId, (SourceId, SortKey)>,
+	next_id: u64,
+}
+
+impl DiagnosticStore {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn add(&mut self, source: &SourceId, error: Error, flag: ErrorFlag, severity: Severity) -> DiagnosticId {
+		let id = DiagnosticId(self.next_id);
+		self.next_id += 1;
+
+		let stored = StoredError { id, error, flag, severity };
+		let key = sort_key(&stored);
+		let errors = self.files.entry(source.clone()).or_default();
+		errors.insert(errors.partition_point(|other| sort_key(other) < key), stored);
+		self.index.insert(id, (source.clone(), key));
+		id
+	}
+
+	/// Переносит результат проверки файла; id выдаются заново, чтобы не пересекаться с другими файлами
+	pub fn extend(&mut self, source: &SourceId, errors: ErrorStorage) -> Vec<DiagnosticId> {
+		errors.into_errors().into_iter().map(|stored| self.add(source, stored.error, stored.flag, stored.severity)).collect()
+	}
+
+	/// Повторный анализ: старые диагностики файла удаляются, новые занимают их место
+	pub fn replace(&mut self, source: &SourceId, errors: ErrorStorage) -> Vec<DiagnosticId> {
+		self.clear_file(source);
+		self.extend(source, errors)
+	}
+
+	pub fn clear_file(&mut self, source: &SourceId) -> usize {
+		let removed = self.files.remove(source).unwrap_or_default();
+		for stored in &removed {
+			self.index.remove(&stored.id);
+		}
+		removed.len()
+	}
+
+	pub fn get(&self, id: DiagnosticId) -> Option<(&SourceId, &StoredError)> {
+		let (source, key) = self.index.get(&id)?;
+		let errors = self.files.get(source)?;
+		let index = errors.binary_search_by_key(key, sort_key).ok()?;
+		Some((source, &errors[index]))
+	}
+
+	pub fn file(&self, source: &SourceId) -> &[StoredError] {
+		self.files.get(source).map_or(&[], Vec::as_slice)
+	}
+
+	/// Диагностики, пересекающие байтовый диапазон. Пустой диапазон — курсор: находятся диагностики под ним.
+	/// Диагностика без конца занимает один байт, без позиции — не пересекает ничего
+	pub fn overlapping(&self, source: &SourceId, range: Range<usize>) -> impl Iterator<Item = &StoredError> {
+		let errors = self.file(source);
+		let end = range.end.max(range.start + 1);
+		let upper = errors.partition_point(|stored| sort_key(stored).0.is_none_or(|start| start < end));
+
+		errors[..upper]
+			.iter()
+			.filter(move |stored| stored.error.pos.as_ref().is_some_and(|pos| pos.end.map_or(pos.start.offset, |end| end.offset).max(pos.start.offset + 1) > range.start))
+	}
+
+	pub fn files(&self) -> impl Iterator<Item = (&SourceId, &[StoredError])> {
+		self.files.iter().map(|(source, errors)| (source, errors.as_slice()))
+	}
+
+	pub fn len(&self) -> usize {
+		self.index.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.index.is_empty()
+	}
+}
+
+fn sort_key(stored: &StoredError) -> SortKey {
+	let pos = stored.error.pos.as_ref();
+	(pos.map(|pos| pos.start.offset), pos.and_then(|pos| pos.end).map(|end| end.offset), stored.id)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::shared::{ErrorKind, Position, SemanticError, UnitError};
+
+	fn unknown(symbol: &str, start: usize, end: usize) -> Error {
+		Error::span(ErrorKind::Semantic(SemanticError::Unit(UnitError::UnknownUnit(symbol.into()))), Position::new(1, start + 1, start), Position::new(1, end + 1, end))
+	}
+
+	fn main_file() -> SourceId {
+		SourceId::Virtual("main.lacon".into())
+	}
+
+	/// Три диагностики в main.lacon (одна без позиции) и одна в lib.lacon
+	fn project() -> (DiagnosticStore, Vec<DiagnosticId>) {
+		let mut store = DiagnosticStore::new();
+		let mut scan = ErrorStorage::new();
+		scan.add(unknown("tile", 20, 24), ErrorFlag::Common);
+		scan.add(unknown("tick", 4, 8), ErrorFlag::Common);
+		scan.add(Error::global(ErrorKind::Runtime), ErrorFlag::Critical);
+		let ids = store.extend(&main_file(), scan);
+
+		let mut scan = ErrorStorage::new();
+		scan.add(unknown("cell", 4, 8), ErrorFlag::Common);
+		store.extend(&SourceId::Virtual("lib.lacon".into()), scan);
+		(store, ids)
+	}
+
+	#[test]
+	fn ids_are_unique_in_the_project() {
+		// Id из хранилища сканера не переносятся
+		let (store, ids) = project();
+		assert_eq!(ids, [DiagnosticId(0), DiagnosticId(1), DiagnosticId(2)]);
+		assert_eq!(store.file(&SourceId::Virtual("lib.lacon".into()))[0].id, DiagnosticId(3));
+	}
+
+	#[test]
+	fn files_are_sorted_by_position() {
+		let (store, ids) = project();
+		let order: Vec<_> = store.file(&main_file()).iter().map(|stored| stored.error.kind.code()).collect();
+		assert_eq!(order, ["R0001", "U0003", "U0003"]);
+		assert_eq!(store.get(ids[1]).map(|(source, stored)| (source.clone(), stored.error.pos.as_ref().unwrap().start.offset)), Some((main_file(), 4)));
+	}
+
+	#[test]
+	fn ranges_find_overlapping_diagnostics() {
+		let (store, _) = project();
+		let hits = |range| store.overlapping(&main_file(), range).map(|stored| stored.error.pos.as_ref().unwrap().start.offset).collect::<Vec<_>>();
+		assert_eq!(hits(0..21), [4, 20]);
+		assert_eq!(hits(8..20), Vec::<usize>::new());
+		assert_eq!(hits(6..6), [4]);
+	}
+
+	#[test]
+	fn replacing_a_file_retires_its_ids() {
+		let (mut store, ids) = project();
+		let mut scan = ErrorStorage::new();
+		scan.add(unknown("rack", 0, 4), ErrorFlag::Common);
+		let fresh = store.replace(&main_file(), scan);
+		assert_eq!((store.file(&main_file()).len(), store.len()), (1, 2));
+		assert!(store.get(ids[0]).is_none() && fresh[0] > ids[2]);
+	}
+}
@@ -1,4 +1,5 @@
-use super::{ErrorFlag, ErrorKind, Position};
+use super::{ErrorFlag, ErrorKind, Position, Severity, Suggestion};
+use crate::shared::SourceId;
 
 #[derive(Debug, Clone)]
 pub struct ErrorPos {
@@ -10,19 +11,38 @@ pub struct ErrorPos {
 pub struct Error {
 	pub kind: ErrorKind,
 	pub pos: Option<ErrorPos>,
+	/// Подпись основного диапазона: `^^ expected <Time>`
+	pub label: Option<String>,
+	/// Другие места, связанные с ошибкой, в том числе в других файлах
+	pub secondary: Vec<Label>,
+	pub notes: Vec<String>,
+	pub suggestions: Vec<Suggestion>,
 }
 
-#[derive(Debug)]
+/// Подписанный диапазон; `source: None` — тот же файл, что у основной позиции
+#[derive(Debug, Clone)]
+pub struct Label {
+	pub source: Option<SourceId>,
+	pub pos: ErrorPos,
+	pub message: String,
+}
+
+/// Идентификатор диагностики; счётчик 64-битный и не переполняется, так что id не повторяются
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DiagnosticId(pub u64);
+
+#[derive(Debug, Clone)]
 pub struct StoredError {
-	pub id: u16,
+	pub id: DiagnosticId,
 	pub error: Error,
 	pub flag: ErrorFlag,
+	pub severity: Severity,
 }
 
 #[derive(Debug)]
 pub struct ErrorStorage {
 	errors: Vec<StoredError>,
-	next_id: u16,
+	next_id: u64,
 }
 
 impl ErrorStorage {
@@ -30,16 +50,21 @@ impl ErrorStorage {
 		Self { errors: Vec::new(), next_id: 0 }
 	}
 
-	pub fn add(&mut self, error: Error, flag: ErrorFlag) -> u16 {
-		let id = self.next_id;
-		self.next_id = self.next_id.wrapping_add(1);
+	pub fn add(&mut self, error: Error, flag: ErrorFlag) -> DiagnosticId {
+		self.add_with_severity(error, flag, Severity::Error)
+	}
+
+	pub fn add_with_severity(&mut self, error: Error, flag: ErrorFlag, severity: Severity) -> DiagnosticId {
+		let id = DiagnosticId(self.next_id);
+		self.next_id += 1;
 
-		self.errors.push(StoredError { id, error, flag });
+		self.errors.push(StoredError { id, error, flag, severity });
 		id
 	}
 
-	pub fn get(&self, id: u16) -> Option<&StoredError> {
-		self.errors.iter().find(|e| e.id == id)
+	// Ошибки добавляются по возрастанию id, поэтому хранилище отсортировано по нему
+	pub fn get(&self, id: DiagnosticId) -> Option<&StoredError> {
+		self.errors.binary_search_by_key(&id, |e| e.id).ok().map(|index| &self.errors[index])
 	}
 
 	pub fn filter_by_kind(&self, kind_to_match: fn(&ErrorKind) -> bool) -> impl Iterator<Item = &StoredError> {
@@ -50,7 +75,16 @@ impl ErrorStorage {
 		self.errors.iter().filter(move |e| e.flag == flag)
 	}
 
+	/// Диагностики не ниже `severity`: `at_least(Severity::Error)` — только настоящие ошибки
+	pub fn at_least(&self, severity: Severity) -> impl Iterator<Item = &StoredError> {
+		self.errors.iter().filter(move |e| e.severity >= severity)
+	}
+
 	pub fn all(&self) -> &[StoredError] {
 		&self.errors
 	}
+
+	pub fn into_errors(self) -> Vec<StoredError> {
+		self.errors
+	}
 }
@@ -0,0 +1,108 @@
+use super::{Error, Locale, Position, localize};
+use serde::Serialize;
+
+/// Ключ каталога `MESSAGES` и аргументы сообщения
+pub type Template = (&'static str, Vec<(&'static str, String)>);
+
+/// Подсказка к диагностике: заметка `help:` и, если правка однозначна, замена текста, которую редактор применит сам
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Suggestion {
+	pub message: String,
+	pub edit: Option<TextEdit>,
+	/// Если подсказка переводится: `message` — её английский текст
+	#[serde(skip)]
+	pub template: Option<Template>,
+}
+
+impl Suggestion {
+	/// Текст на выбранном языке; свободный текст из `with_help` и `with_fix` — как написан
+	pub fn localized(&self, locale: Locale) -> String {
+		match &self.template {
+			Some((key, args)) => localize(key, args, locale),
+			None => self.message.clone(),
+		}
+	}
+}
+
+/// Замена диапазона `start..end` на `replacement`; пустой диапазон — вставка
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TextEdit {
+	pub start: Position,
+	pub end: Position,
+	pub replacement: String,
+}
+
+impl Error {
+	pub fn with_help(mut self, message: impl Into<String>) -> Self {
+		self.suggestions.push(Suggestion {
+			message: message.into(),
+			edit: None,
+			template: None,
+		});
+		self
+	}
+
+	pub fn with_fix(mut self, message: impl Into<String>, start: Position, end: Position, replacement: impl Into<String>) -> Self {
+		let edit = TextEdit { start, end, replacement: replacement.into() };
+		self.suggestions.push(Suggestion {
+			message: message.into(),
+			edit: Some(edit),
+			template: None,
+		});
+		self
+	}
+
+	/// «did you mean `kg`?» с заменой всего диапазона ошибки; без позиции — только заметка
+	pub fn did_you_mean(self, replacement: &str) -> Self {
+		let args = vec![("replacement", replacement.to_string())];
+		let message = localize("did_you_mean", &args, Locale::En);
+		let mut error = match self.pos.as_ref().map(|pos| (pos.start, pos.end)) {
+			Some((start, Some(end))) => self.with_fix(message, start, end, replacement),
+			_ => self.with_help(message),
+		};
+		if let Some(suggestion) = error.suggestions.last_mut() {
+			suggestion.template = Some(("did_you_mean", args));
+		}
+		error
+	}
+}
+
+/// Расстояние Дамерау — Левенштейна (перестановка соседних символов — одна правка) по символам
+pub fn edit_distance(a: &str, b: &str) -> usize {
+	let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+	let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+	for (i, row) in rows.iter_mut().enumerate() {
+		row[0] = i;
+	}
+	for j in 0..=b.len() {
+		rows[0][j] = j;
+	}
+
+	for i in 1..=a.len() {
+		for j in 1..=b.len() {
+			let cost = usize::from(a[i - 1] != b[j - 1]);
+			let mut best = (rows[i - 1][j] + 1).min(rows[i][j - 1] + 1).min(rows[i - 1][j - 1] + cost);
+			if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+				best = best.min(rows[i - 2][j - 2] + 1);
+			}
+			rows[i][j] = best;
+		}
+	}
+
+	rows[a.len()][b.len()]
+}
+
+/// Ближайший кандидат не дальше трети длины слова (минимум одна правка).
+/// Сначала сравнивается без учёта регистра, так что к `Kg` ближе `kg`, чем `Mg`; при равенстве побеждает первый
+pub fn closest<'a>(word: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+	let limit = (word.chars().count() / 3).max(1);
+	let lower = word.to_lowercase();
+
+	candidates
+		.into_iter()
+		.filter(|candidate| *candidate != word)
+		.map(|candidate| ((edit_distance(&lower, &candidate.to_lowercase()), edit_distance(word, candidate)), candidate))
+		.filter(|((folded, _), _)| *folded <= limit)
+		.min_by_key(|(rank, _)| *rank)
+		.map(|(_, candidate)| candidate)
+}
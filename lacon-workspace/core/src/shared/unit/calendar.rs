@@ -0,0 +1,186 @@
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+
+/// Календарная длительность: месяц и год не имеют фиксированной длины в секундах
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum CalendarUnit {
+	Day,
+	Week,
+	Month,
+	Year,
+}
+
+impl CalendarUnit {
+	/// Дни и недели переводятся друг в друга, месяцы и годы — тоже, но не между группами
+	pub fn is_month_based(self) -> bool {
+		matches!(self, CalendarUnit::Month | CalendarUnit::Year)
+	}
+
+	/// Сколько дней (для Day/Week) или месяцев (для Month/Year) в одной единице
+	pub fn count(self) -> i64 {
+		match self {
+			CalendarUnit::Day | CalendarUnit::Month => 1,
+			CalendarUnit::Week => 7,
+			CalendarUnit::Year => 12,
+		}
+	}
+}
+
+/// Дата пролептического григорианского календаря
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CalendarDate {
+	pub year: i32,
+	pub month: u8,
+	pub day: u8,
+}
+
+impl CalendarDate {
+	pub fn new(year: i32, month: u8, day: u8) -> Option<Self> {
+		((1..=12).contains(&month) && day >= 1 && day <= days_in_month(year, month)).then_some(Self { year, month, day })
+	}
+
+	/// Календарное сложение: `2026-01-31 + 1 month` = `2026-02-28`, день прижимается к концу месяца
+	pub fn add(self, amount: i64, unit: CalendarUnit) -> Option<Self> {
+		let amount = amount.checked_mul(unit.count())?;
+
+		if !unit.is_month_based() {
+			return Self::from_days(self.to_days().checked_add(amount)?);
+		}
+
+		let months = i64::from(self.year) * 12 + i64::from(self.month) - 1 + amount;
+		let year = i32::try_from(months.div_euclid(12)).ok()?;
+		let month = months.rem_euclid(12) as u8 + 1;
+
+		Some(Self {
+			year,
+			month,
+			day: self.day.min(days_in_month(year, month)),
+		})
+	}
+
+	/// Точное сложение: только целые сутки, `None` для `12h` и при выходе за пределы календаря
+	pub fn add_seconds(self, seconds: f64) -> Option<Self> {
+		let days = seconds / 86400.0;
+		if !days.is_finite() || (days - days.round()).abs() > 1e-9 || days.abs() > i64::MAX as f64 {
+			return None;
+		}
+
+		Self::from_days(self.to_days().checked_add(days.round() as i64)?)
+	}
+
+	/// Дни от 1970-01-01
+	pub fn to_days(self) -> i64 {
+		let year = i64::from(self.year) - i64::from(self.month <= 2);
+		let era = year.div_euclid(400);
+		let year_of_era = year.rem_euclid(400);
+		let month = i64::from(self.month);
+		let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(self.day) - 1;
+		let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+		era * 146097 + day_of_era - 719468
+	}
+
+	/// Дата по числу дней от 1970-01-01; `None`, если год не помещается в `i32`
+	pub fn from_days(days: i64) -> Option<Self> {
+		let days = days.checked_add(719468)?;
+		let era = days.div_euclid(146097);
+		let day_of_era = days.rem_euclid(146097);
+		let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+		let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+		let mp = (5 * day_of_year + 2) / 153;
+		let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u8;
+		let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+		let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+		Some(Self { year: i32::try_from(year).ok()?, month, day })
+	}
+}
+
+pub fn is_leap_year(year: i32) -> bool {
+	(year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+pub fn days_in_month(year: i32, month: u8) -> u8 {
+	match month {
+		2 if is_leap_year(year) => 29,
+		2 => 28,
+		4 | 6 | 9 | 11 => 30,
+		_ => 31,
+	}
+}
+
+impl FromStr for CalendarDate {
+	type Err = ();
+
+	/// `YYYY-MM-DD`
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut parts = s.splitn(3, '-');
+		let year = parts.next().and_then(|p| p.parse().ok()).ok_or(())?;
+		let month = parts.next().and_then(|p| p.parse().ok()).ok_or(())?;
+		let day = parts.next().and_then(|p| p.parse().ok()).ok_or(())?;
+
+		Self::new(year, month, day).ok_or(())
+	}
+}
+
+impl fmt::Display for CalendarDate {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::shared::{UnitContext, UnitError};
+
+	fn date(s: &str) -> CalendarDate {
+		s.parse().expect(s)
+	}
+
+	#[test]
+	fn seconds_add_whole_days() {
+		assert_eq!(date("2026-03-01").add_seconds(-86400.0), Some(date("2026-02-28")));
+		assert_eq!(date("2026-03-01").add_seconds(2.0 * 86400.0), Some(date("2026-03-03")));
+		// Половина суток не сдвигает дату ни вперёд, ни назад — это ошибка
+		assert_eq!(date("2026-03-01").add_seconds(43200.0), None);
+		assert_eq!(date("2026-03-01").add_seconds(-43200.0), None);
+	}
+
+	#[test]
+	fn months_clamp_to_their_end() {
+		let ctx = UnitContext::new();
+		assert_eq!(ctx.add_to_date(date("2026-01-31"), 1.0, &ctx.unit("calmonth")).unwrap(), date("2026-02-28"));
+		assert_eq!(ctx.add_to_date(date("2024-01-31"), 1.0, &ctx.unit("calmonth")).unwrap(), date("2024-02-29"));
+		assert_eq!(ctx.add_to_date(date("2024-02-29"), 2.0, &ctx.unit("calyear")).unwrap(), date("2026-02-28"));
+		assert!(ctx.add_to_date(date("2026-01-31"), 1.5, &ctx.unit("calmonth")).is_err());
+	}
+
+	#[test]
+	fn durations_add_to_dates() {
+		let ctx = UnitContext::new();
+		assert_eq!(ctx.add_to_date(date("2026-03-01"), -1.0, &ctx.unit("calday")).unwrap(), date("2026-02-28"));
+		assert_eq!(ctx.add_to_date(date("2026-01-31"), 30.0, &ctx.unit("day")).unwrap(), date("2026-03-02"));
+		assert_eq!(ctx.add_to_date(date("2026-03-01"), -24.0, &ctx.unit("h")).unwrap(), date("2026-02-28"));
+		assert!(matches!(ctx.add_to_date(date("2026-03-01"), -12.0, &ctx.unit("h")), Err(UnitError::AmbiguousCalendar(..))));
+	}
+
+	#[test]
+	fn calendar_units_do_not_become_seconds() {
+		let ctx = UnitContext::new();
+		assert_eq!(ctx.convert(2.0, &ctx.unit("calyear"), &ctx.unit("calmonth")), Some(24.0));
+		assert_eq!(ctx.convert(1.0, &ctx.unit("calmonth"), &ctx.unit("s")), None);
+		assert_eq!(ctx.convert(1.0, &ctx.unit("calmonth"), &ctx.unit("month")), None);
+		assert_eq!(ctx.convert(1.0, &ctx.unit("calweek"), &ctx.unit("calmonth")), None);
+		assert_eq!(ctx.convert(1.0, &ctx.unit("month"), &ctx.unit("s")), Some(2629746.0));
+	}
+
+	#[test]
+	fn days_outside_calendar() {
+		assert_eq!(CalendarDate::from_days(0), Some(date("1970-01-01")));
+		assert_eq!(CalendarDate::from_days(i64::MAX), None);
+		assert_eq!(CalendarDate::from_days(i64::from(i32::MAX) * 366), None);
+		assert_eq!(date("2026-01-01").add(i64::MAX / 7, CalendarUnit::Week), None);
+	}
+}
@@ -1,10 +1,12 @@
-use strum_macros::EnumIter;
+use serde::Serialize;
+use strum_macros::{EnumIter, EnumString};
 
 use super::UnitKind;
-use super::{CalcMode, UnitProps};
+use super::{CalcMode, UnitId, UnitProps};
+use std::borrow::Cow;
 use std::collections::BTreeMap;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, Ord, PartialOrd)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, EnumString, Ord, PartialOrd, Serialize)]
 pub enum PrefixGroup {
 	SI,
 	Thermal, // Temperature
@@ -13,46 +15,141 @@ pub enum PrefixGroup {
 	Digital,
 }
 
+/// Система единиц, к которой относится юнит; профили (`UnitProfile`) разрешают наборы систем
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, EnumString, Ord, PartialOrd, Serialize)]
+pub enum UnitSystem {
+	SI,
+	Metric,    // Внесистемные, допустимые вместе с SI: L, t, h, eV
+	Customary, // Американская/имперская: ft, mi, lb, °F
+	CGS,       // erg, St
+	Historic,  // Вышедшие из употребления: Ci, °Re, °Ro
+	Universal, // Доли, информация, календарь, вёрстка — разрешены в любом профиле
+}
+
+impl PrefixGroup {
+	/// Можно ли приписать юниту этой группы префикс из группы `prefix`: `Digital` понимает и SI (`kbit`), и двоичные (`Kibit`)
+	pub fn accepts(self, prefix: PrefixGroup) -> bool {
+		self == prefix || (self == PrefixGroup::Digital && prefix == PrefixGroup::SI)
+	}
+}
+
+/// Названия юнита на другом языке. Для "ru" первые три формы — для 1, 2 и 5 (`метр`, `метра`, `метров`),
+/// остальные (`секунду`) принимаются только при разборе
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct UnitAlias {
+	pub locale: &'static str,
+	pub forms: &'static [&'static str],
+}
+
+impl UnitAlias {
+	pub const fn en(forms: &'static [&'static str]) -> Self {
+		Self { locale: "en", forms }
+	}
+
+	pub const fn ru(forms: &'static [&'static str]) -> Self {
+		Self { locale: "ru", forms }
+	}
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct UnitNames {
+	pub singular: &'static str,
+	pub plural: &'static str,
+	pub aliases: &'static [UnitAlias],
+}
+
+impl UnitNames {
+	pub const NONE: Self = Self { singular: "", plural: "", aliases: &[] };
+
+	/// Все написания для разбора: `meter`, `meters`, `metre`, `метров`...
+	pub fn all(&self) -> impl Iterator<Item = &'static str> {
+		[self.singular, self.plural].into_iter().chain(self.aliases.iter().flat_map(|alias| alias.forms.iter().copied())).filter(|name| !name.is_empty())
+	}
+
+	/// Форма для количества `count` на языке `locale`
+	pub fn form(&self, locale: &str, count: f64) -> Option<&'static str> {
+		if locale == "en" {
+			let name = if count.abs() == 1.0 { self.singular } else { self.plural };
+			return (!name.is_empty()).then_some(name);
+		}
+
+		let forms = self.aliases.iter().find(|alias| alias.locale == locale)?.forms;
+		let index = match locale {
+			"ru" => russian_plural(count),
+			_ => usize::from(count.abs() != 1.0),
+		};
+		forms.get(index).or(forms.first()).copied()
+	}
+}
+
+// 1 метр, 2 метра, 5 метров, 21 метр, 1.5 метра
+fn russian_plural(count: f64) -> usize {
+	if count.fract() != 0.0 {
+		return 1;
+	}
+
+	let n = (count.abs() as u64) % 100;
+	match (n % 10, n) {
+		(_, 11..=14) => 2,
+		(1, _) => 0,
+		(2..=4, _) => 1,
+		_ => 2,
+	}
+}
+
 #[derive(Debug, Clone)]
 pub struct UnitDef {
-	pub symbol: &'static str,
+	// Встроенные юниты ссылаются на статические строки, пользовательские владеют своими
+	pub symbol: Cow<'static, str>,
 	pub dimension: UnitKind,
-	pub parts: Option<(&'static str, &'static str)>,
 	pub props: UnitProps,
-	pub numerator_group: PrefixGroup,
-	pub denominator_group: PrefixGroup,
+	pub prefix_group: PrefixGroup,
+	pub names: UnitNames,
+	pub system: UnitSystem,
 }
 
 impl UnitDef {
 	pub const DEFAULT: Self = Self {
-		symbol: "",
+		symbol: Cow::Borrowed(""),
 		dimension: UnitKind::None,
-		parts: None,
-		numerator_group: PrefixGroup::None,
-		denominator_group: PrefixGroup::None,
+		prefix_group: PrefixGroup::None,
 		props: UnitProps::DEFAULT,
+		names: UnitNames::NONE,
+		system: UnitSystem::SI,
 	};
 
 	pub const SI: Self = Self {
-		symbol: "",
+		symbol: Cow::Borrowed(""),
 		dimension: UnitKind::None,
-		parts: None,
-		numerator_group: PrefixGroup::SI,
-		denominator_group: PrefixGroup::SI,
+		prefix_group: PrefixGroup::SI,
 		props: UnitProps::DEFAULT,
+		names: UnitNames::NONE,
+		system: UnitSystem::SI,
 	};
 
-	pub const fn new(symbol: &'static str, dimension: UnitKind, parts: Option<(&'static str, &'static str)>, n_grp: PrefixGroup, d_grp: PrefixGroup, props: UnitProps) -> Self {
+	pub const fn new(symbol: &'static str, dimension: UnitKind, p_grp: PrefixGroup, props: UnitProps) -> Self {
 		Self {
-			symbol,
+			symbol: Cow::Borrowed(symbol),
 			dimension,
-			parts,
-			numerator_group: n_grp,
-			denominator_group: d_grp,
+			prefix_group: p_grp,
 			props,
+			names: UnitNames::NONE,
+			system: UnitSystem::SI,
 		}
 	}
 
+	/// `UnitDef::new(..).named("meter", "meters", &[UnitAlias::ru(&["метр", "метра", "метров"])])`
+	pub const fn named(mut self, singular: &'static str, plural: &'static str, aliases: &'static [UnitAlias]) -> Self {
+		self.names = UnitNames { singular, plural, aliases };
+		self
+	}
+
+	/// По умолчанию юнит относится к SI
+	pub const fn in_system(mut self, system: UnitSystem) -> Self {
+		self.system = system;
+		self
+	}
+
 	pub fn get_props(&self) -> &UnitProps {
 		&self.props
 	}
@@ -63,7 +160,8 @@ impl UnitDef {
 		let props = self.get_props();
 
 		match props.mode {
-			CalcMode::Linear => (value * props.scale) + props.offset,
+			// Относительные считаются в единицах своей базы (кегль, пиксель), её подставляет RelativeContext
+			CalcMode::Linear | CalcMode::Relative(_) | CalcMode::Calendar(_) => (value * props.scale) + props.offset,
 			CalcMode::Exponential => {
 				// K = T0 * (T1 / T0)^(G / 100)
 				let t0 = props.offset;
@@ -83,7 +181,7 @@ impl UnitDef {
 		let props = self.get_props();
 
 		match props.mode {
-			CalcMode::Linear => (base_value - props.offset) / props.scale,
+			CalcMode::Linear | CalcMode::Relative(_) | CalcMode::Calendar(_) => (base_value - props.offset) / props.scale,
 			CalcMode::Exponential => {
 				// G = 100 * log_{T1/T0}(K/T0)
 				let t0 = props.offset;
@@ -100,31 +198,31 @@ impl UnitDef {
 	}
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct UnitNode {
 	pub children: BTreeMap<u8, UnitNode>,
-	pub is_final: bool,
+	pub unit: Option<UnitId>,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct UnitTree {
 	pub root: UnitNode,
 }
 
 impl UnitTree {
-	pub fn insert(&mut self, key: &str) {
+	pub fn insert(&mut self, key: &str, unit: UnitId) {
 		let mut current_node = &mut self.root;
 
 		for byte in key.as_bytes() {
 			current_node = current_node.children.entry(*byte).or_default();
 		}
 
-		current_node.is_final = true;
+		current_node.unit = Some(unit);
 	}
 
-	pub fn longest_match(&self, input: &[u8]) -> usize {
+	pub fn longest_match(&self, input: &[u8]) -> Option<(usize, UnitId)> {
 		let mut current_node = &self.root;
-		let mut last_final_byte_idx = 0;
+		let mut last_match = None;
 		let mut current_byte_idx = 0;
 
 		for &b in input {
@@ -132,28 +230,40 @@ impl UnitTree {
 				current_node = next_node;
 				current_byte_idx += 1;
 
-				if current_node.is_final {
-					last_final_byte_idx = current_byte_idx;
+				if let Some(unit) = current_node.unit {
+					last_match = Some((current_byte_idx, unit));
 				}
 			} else {
 				break;
 			}
 		}
 
-		last_final_byte_idx
+		last_match
 	}
 }
 
 #[macro_export]
 macro_rules! units_array {
+    // Обработка @multi с системой единиц: `@multi [..] "F", .., UnitProps {..} => UnitSystem::Customary,`
+    (
+        [$($accumulated:tt)*]
+        @multi [$($symbol:expr),+] $suffix:expr, $dim:expr, $pg:expr, $props:expr => $system:expr,
+        $($rest:tt)*
+    ) => {
+        units_array![
+            [$($accumulated)* $(UnitDef::new(concat!($symbol, $suffix), $dim, $pg, $props).in_system($system),)+]
+            $($rest)*
+        ]
+    };
+
     // Обработка @multi
     (
         [$($accumulated:tt)*]
-        @multi [$($symbol:expr),+] $suffix:expr, $dim:expr, ($ng:expr, $dg:expr), $props:expr,
+        @multi [$($symbol:expr),+] $suffix:expr, $dim:expr, $pg:expr, $props:expr,
         $($rest:tt)*
     ) => {
         units_array![
-            [$($accumulated)* $(UnitDef::new(concat!($symbol, $suffix), $dim, None, $ng, $dg, $props),)+]
+            [$($accumulated)* $(UnitDef::new(concat!($symbol, $suffix), $dim, $pg, $props),)+]
             $($rest)*
         ]
     };
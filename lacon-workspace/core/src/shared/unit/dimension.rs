@@ -0,0 +1,130 @@
+use super::UnitKind;
+use std::ops::{Div, Mul};
+use strum::IntoEnumIterator;
+
+const BASE_COUNT: usize = 9;
+/// Слоты под базовые величины, объявленные пользователем (`requisition`, `cell`...)
+pub const CUSTOM_BASE_COUNT: usize = 6;
+const SLOT_COUNT: usize = BASE_COUNT + CUSTOM_BASE_COUNT;
+
+/// Размерность как вектор степеней базовых величин: m/s2 = Length^1 Time^-2
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Dimension(pub [i8; SLOT_COUNT]);
+
+impl Dimension {
+	/// Величины без формулы разложения, из которых собираются все остальные
+	pub const BASES: [UnitKind; BASE_COUNT] = [
+		UnitKind::Length,
+		UnitKind::Mass,
+		UnitKind::Time,
+		UnitKind::ElectricCurrent,
+		UnitKind::Temperature,
+		UnitKind::AmountOfSubstance,
+		UnitKind::LuminousIntensity,
+		UnitKind::Information,
+		UnitKind::Angle,
+	];
+
+	/// Когерентные юниты базовых величин в том же порядке
+	pub const BASE_SYMBOLS: [&str; BASE_COUNT] = ["m", "kg", "s", "A", "K", "mol", "cd", "bit", "rad"];
+
+	pub const NONE: Self = Self([0; SLOT_COUNT]);
+
+	pub fn of(kind: UnitKind) -> Self {
+		if let Some(index) = Self::BASES.iter().position(|base| *base == kind) {
+			return Self::slot(index);
+		}
+
+		let formula = kind.formula();
+		let num = formula.num.iter().fold(Self::NONE, |acc, (k, e)| acc * Self::of(*k).powi(*e));
+		formula.den.iter().fold(num, |acc, (k, e)| acc / Self::of(*k).powi(*e))
+	}
+
+	/// Пользовательская базовая величина с номером `index` (`< CUSTOM_BASE_COUNT`)
+	pub fn custom(index: usize) -> Option<Self> {
+		(index < CUSTOM_BASE_COUNT).then(|| Self::slot(BASE_COUNT + index))
+	}
+
+	fn slot(index: usize) -> Self {
+		let mut exponents = [0; SLOT_COUNT];
+		exponents[index] = 1;
+		Self(exponents)
+	}
+
+	pub fn powi(self, exponent: i8) -> Self {
+		Self(self.0.map(|e| e.saturating_mul(exponent)))
+	}
+
+	pub fn is_dimensionless(&self) -> bool {
+		*self == Self::NONE
+	}
+
+	/// Первая именованная величина с такой размерностью (порядок `UnitKind` задаёт приоритет)
+	pub fn kind(&self) -> UnitKind {
+		UnitKind::iter().filter(|kind| *kind != UnitKind::None).find(|kind| Self::of(*kind) == *self).unwrap_or(UnitKind::None)
+	}
+}
+
+impl Mul for Dimension {
+	type Output = Self;
+
+	fn mul(self, rhs: Self) -> Self {
+		let mut exponents = self.0;
+		for (e, r) in exponents.iter_mut().zip(rhs.0) {
+			*e = e.saturating_add(r);
+		}
+		Self(exponents)
+	}
+}
+
+impl Div for Dimension {
+	type Output = Self;
+
+	fn div(self, rhs: Self) -> Self {
+		self * rhs.powi(-1)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::shared::UnitContext;
+
+	fn convert(value: f64, from: &str, to: &str) -> Option<f64> {
+		let ctx = UnitContext::new();
+		ctx.convert(value, &ctx.unit(from), &ctx.unit(to))
+	}
+
+	fn close(value: Option<f64>, expected: f64) -> bool {
+		value.is_some_and(|value| (value - expected).abs() < 1e-9)
+	}
+
+	#[test]
+	fn angles_share_one_dimension() {
+		assert!(close(convert(180.0, "\u{00B0}", "rad"), std::f64::consts::PI));
+		assert!(close(convert(1.0, "turn", "grad"), 400.0));
+		assert!(close(convert(1.0, "deg", "\u{2032}"), 60.0));
+		assert!(close(convert(1.0, "arcmin", "arcsec"), 60.0));
+		assert!(close(convert(1000.0, "mrad", "rad"), 1.0));
+
+		let ctx = UnitContext::new();
+		assert_eq!(ctx.unit("\u{00B0}C").kind, UnitKind::Temperature);
+		assert_eq!(ctx.unit("\u{00B0}").kind, UnitKind::Angle);
+	}
+
+	#[test]
+	fn solid_angles_are_squared_angles() {
+		assert_eq!(UnitContext::new().unit("rad2").kind, UnitKind::SolidAngle);
+		assert!(close(convert(1.0, "cd*sr", "lm"), 1.0));
+		assert!(convert(1.0, "lm", "cd").is_none());
+	}
+
+	#[test]
+	fn rotation_is_angle_per_time() {
+		assert!(close(convert(60.0, "rpm", "rad/s"), std::f64::consts::TAU));
+		assert!(close(convert(60.0, "rpm", "Hz"), 1.0));
+		assert!(close(convert(1.0, "Hz", "deg/s"), 360.0));
+		assert!(convert(1.0, "rad", "Hz").is_none());
+		assert!(convert(1.0, "Bq", "rpm").is_none());
+	}
+}
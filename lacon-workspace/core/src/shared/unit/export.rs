@@ -0,0 +1,159 @@
+use super::prefixes::{PREFIX_NAMES, PREFIXES};
+use super::{CalcMode, Dimension, FormatOptions, PrefixGroup, UNITS, UnitContext, UnitId, UnitKind, UnitNames, UnitSystem};
+use serde::Serialize;
+use strum::IntoEnumIterator;
+
+/// Весь реестр одним значением для редакторов, документации и фронтенда: `serde_json::to_string(&ctx.export_registry())`
+#[derive(Debug, Clone, Serialize)]
+pub struct RegistryExport {
+	/// Символы базовых величин в порядке слотов `Dimension`, включая объявленные пользователем
+	pub bases: Vec<String>,
+	pub units: Vec<UnitExport>,
+	pub prefixes: Vec<PrefixExport>,
+	pub kinds: Vec<KindExport>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UnitExport {
+	pub id: u16,
+	pub symbol: String,
+	pub kind: UnitKind,
+	/// Размерность в базовых единицах: `m²⋅kg/s²`
+	pub formula: String,
+	pub dimension: Vec<(String, i8)>,
+	pub scale: f64,
+	/// Точный множитель, если задан определением: `0.3048`, `1/72`
+	pub exact: Option<String>,
+	pub offset: f64,
+	pub mode: CalcMode,
+	pub prefix_group: PrefixGroup,
+	/// Группы префиксов, которые юнит принимает (`Digital` берёт и SI)
+	pub accepted_prefixes: Vec<PrefixGroup>,
+	pub system: UnitSystem,
+	pub names: UnitNames,
+	pub user_defined: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PrefixExport {
+	pub symbol: &'static str,
+	pub factor: f64,
+	pub group: PrefixGroup,
+	pub name_en: Option<&'static str>,
+	pub name_ru: Option<&'static str>,
+}
+
+/// Разложение именованной величины: формула через другие величины и итоговые степени базовых
+#[derive(Debug, Clone, Serialize)]
+pub struct KindExport {
+	pub kind: UnitKind,
+	pub numerator: Vec<(UnitKind, i8)>,
+	pub denominator: Vec<(UnitKind, i8)>,
+	pub dimension: Vec<(String, i8)>,
+	pub formula: String,
+}
+
+impl UnitContext {
+	pub fn export_registry(&self) -> RegistryExport {
+		let options = FormatOptions::DEFAULT;
+		let user_ids = (0..self.user_units.len()).map(|index| UnitId((UNITS.len() + index) as u16));
+		let ids = (0..UNITS.len()).map(|index| UnitId(index as u16)).chain(user_ids);
+
+		let units = ids
+			.filter_map(|id| {
+				let def = self.definition(id)?;
+				let dimension = self.dimension(id)?;
+
+				Some(UnitExport {
+					id: id.0,
+					symbol: def.symbol.to_string(),
+					kind: def.dimension,
+					formula: self.format_dimension(&dimension, &options),
+					dimension: self.dimension_exponents(&dimension),
+					scale: def.props.scale,
+					exact: def.props.exact.map(|exact| exact.to_string()),
+					offset: def.props.offset,
+					mode: def.props.mode.clone(),
+					prefix_group: def.prefix_group,
+					accepted_prefixes: PrefixGroup::iter().filter(|group| *group != PrefixGroup::None && def.prefix_group.accepts(*group)).collect(),
+					system: def.system,
+					names: def.names,
+					user_defined: usize::from(id.0) >= UNITS.len(),
+				})
+			})
+			.collect();
+
+		let prefixes = PREFIXES
+			.iter()
+			.map(|(symbol, factor, group)| {
+				let names = PREFIX_NAMES.iter().find(|(name_symbol, ..)| name_symbol == symbol);
+				PrefixExport {
+					symbol,
+					factor: *factor,
+					group: *group,
+					name_en: names.map(|(_, en, _)| *en),
+					name_ru: names.map(|(_, _, ru)| *ru),
+				}
+			})
+			.collect();
+
+		let kinds = UnitKind::iter()
+			.filter(|kind| *kind != UnitKind::None)
+			.map(|kind| {
+				let formula = kind.formula();
+				let dimension = Dimension::of(kind);
+				KindExport {
+					kind,
+					numerator: formula.num.to_vec(),
+					denominator: formula.den.to_vec(),
+					dimension: self.dimension_exponents(&dimension),
+					formula: self.format_dimension(&dimension, &options),
+				}
+			})
+			.collect();
+
+		RegistryExport {
+			bases: self.dimension_exponents(&Dimension([1; _])).into_iter().map(|(symbol, _)| symbol).collect(),
+			units,
+			prefixes,
+			kinds,
+		}
+	}
+
+	/// Реестр в JSON
+	pub fn export_registry_json(&self) -> serde_json::Result<String> {
+		serde_json::to_string_pretty(&self.export_registry())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::shared::{UnitBase, UnitDeclaration};
+
+	#[test]
+	fn builtin_registry_exports() {
+		let registry = UnitContext::new().export_registry();
+		assert_eq!(registry.units.len(), UNITS.len());
+		assert_eq!(registry.prefixes.len(), PREFIXES.len());
+
+		let ft = registry.units.iter().find(|unit| unit.symbol == "ft").expect("ft");
+		assert_eq!((ft.kind, ft.system, ft.exact.as_deref()), (UnitKind::Length, UnitSystem::Customary, Some("0.3048")));
+		let newton = registry.kinds.iter().find(|kind| kind.kind == UnitKind::Force).expect("Force");
+		assert_eq!(newton.dimension, [("m".to_string(), 1), ("kg".to_string(), 1), ("s".to_string(), -2)]);
+	}
+
+	#[test]
+	fn declared_units_export() {
+		let mut ctx = UnitContext::new();
+		ctx.declare(UnitDeclaration::new("requisition", UnitBase::Dimension("Requisition".into())).with_prefixes(PrefixGroup::SI)).expect("requisition");
+		let registry = ctx.export_registry();
+		assert_eq!(registry.units.len(), UNITS.len() + 1);
+		assert_eq!(registry.bases.last().map(String::as_str), Some("Requisition"));
+		assert!(registry.units.last().is_some_and(|unit| unit.user_defined && unit.symbol == "requisition"));
+
+		let json: serde_json::Value = serde_json::from_str(&ctx.export_registry_json().expect("serialized")).expect("json");
+		assert_eq!(json["units"].as_array().map(Vec::len), Some(UNITS.len() + 1));
+		assert_eq!(json["prefixes"][0]["group"], "SI");
+	}
+}
@@ -0,0 +1,215 @@
+use super::{Dimension, ResolvedUnit, UnitContext, UnitFactor, UnitKind};
+use crate::shared::characters::{DOT_OPERATOR_SIGN, MIDDLE_DOT_SIGN, SUP_0_SIGN, SUP_1_SIGN, SUP_2_SIGN, SUP_3_SIGN, SUP_4_SIGN, SUP_5_SIGN, SUP_6_SIGN, SUP_7_SIGN, SUP_8_SIGN, SUP_9_SIGN, SUP_MINUS_SIGN};
+use strum::IntoEnumIterator;
+
+const SUPERSCRIPT_DIGITS: [&[u8]; 10] = [SUP_0_SIGN, SUP_1_SIGN, SUP_2_SIGN, SUP_3_SIGN, SUP_4_SIGN, SUP_5_SIGN, SUP_6_SIGN, SUP_7_SIGN, SUP_8_SIGN, SUP_9_SIGN];
+
+type Parsed = Option<(usize, Vec<UnitFactor>)>;
+
+/// Грамматика юнит-выражений:
+///
+/// ```text
+/// expression := product ('/' product)*
+/// product    := power (('*' | '⋅' | '·') power)*
+/// power      := atom exponent?
+/// atom       := '(' expression ')' | prefix? symbol
+/// exponent   := digits | '^' '-'? digits | '⁻'? superscript-digits
+/// ```
+///
+/// Всё после `/` до следующего `/` уходит в знаменатель: `J/mol⋅K` = J / (mol⋅K).
+/// Если продолжение не разбирается (`m/2`, `kg*x`), разбор останавливается перед оператором.
+pub struct UnitParser<'ctx, 'input> {
+	context: &'ctx UnitContext,
+	input: &'input [u8],
+}
+
+impl<'ctx, 'input> UnitParser<'ctx, 'input> {
+	pub fn new(context: &'ctx UnitContext, input: &'input [u8]) -> Self {
+		Self { context, input }
+	}
+
+	pub fn parse(&self) -> Option<(usize, ResolvedUnit)> {
+		let (end, factors) = self.expression(0)?;
+		Some((end, self.compose(factors)?))
+	}
+
+	/// Юнит из готовых множителей: размерность, величина и множитель считаются так же, как при разборе
+	pub fn compose(&self, factors: Vec<UnitFactor>) -> Option<ResolvedUnit> {
+		let mut dimension = Dimension::NONE;
+		let mut scale = 1.0;
+
+		for factor in &factors {
+			let def = self.context.definition(factor.unit)?;
+			let prefix = factor.prefix.map_or(1.0, |p| p.factor);
+
+			dimension = dimension * self.context.dimension(factor.unit)?.powi(factor.exponent);
+			scale *= (prefix * def.props.scale).powi(i32::from(factor.exponent));
+		}
+
+		// Одиночный юнит сохраняет свою величину: `%` — это Percent, а не просто Scalar.
+		// Состав выражения важнее размерности: `N⋅m` — Torque, хотя у `J` та же размерность.
+		// Формула, общая для нескольких величин (`J/kg` — и Gy, и Sv), величину не называет
+		let (kind, inferred) = match factors.as_slice() {
+			[single] if single.exponent == 1 => (self.context.definition(single.unit)?.dimension, false),
+			_ => match self.formula_kinds(&factors).as_slice() {
+				[kind] => (*kind, false),
+				[kind, ..] => (*kind, true),
+				[] => (dimension.kind(), true),
+			},
+		};
+
+		Some(ResolvedUnit { factors, dimension, kind, inferred, scale })
+	}
+
+	// Величины, формула которых совпадает с величинами множителей: N (Force) ⋅ m (Length) = Torque
+	fn formula_kinds(&self, factors: &[UnitFactor]) -> Vec<UnitKind> {
+		let mut parts: Vec<(UnitKind, i8)> = Vec::new();
+		for factor in factors {
+			let Some(def) = self.context.definition(factor.unit) else {
+				return Vec::new();
+			};
+			let kind = def.dimension;
+			match parts.iter_mut().find(|(k, _)| *k == kind) {
+				Some((_, exponent)) => *exponent = exponent.saturating_add(factor.exponent),
+				None => parts.push((kind, factor.exponent)),
+			}
+		}
+		parts.retain(|(_, exponent)| *exponent != 0);
+
+		UnitKind::iter()
+			.filter(|kind| {
+				let formula = kind.formula();
+				let expected: Vec<(UnitKind, i8)> = formula.num.iter().copied().chain(formula.den.iter().map(|(k, e)| (*k, -e))).collect();
+				!expected.is_empty() && expected.len() == parts.len() && expected.iter().all(|part| parts.contains(part))
+			})
+			.collect()
+	}
+
+	fn expression(&self, pos: usize) -> Parsed {
+		let (mut pos, mut factors) = self.product(pos)?;
+
+		while self.input.get(pos) == Some(&b'/') {
+			let Some((next, den)) = self.product(pos + 1) else {
+				break;
+			};
+			factors.extend(den.into_iter().map(|f| UnitFactor { exponent: f.exponent.saturating_neg(), ..f }));
+			pos = next;
+		}
+
+		Some((pos, factors))
+	}
+
+	fn product(&self, pos: usize) -> Parsed {
+		let (mut pos, mut factors) = self.power(pos)?;
+
+		while let Some(sep_len) = self.separator(pos) {
+			let Some((next, more)) = self.power(pos + sep_len) else {
+				break;
+			};
+			factors.extend(more);
+			pos = next;
+		}
+
+		Some((pos, factors))
+	}
+
+	fn power(&self, pos: usize) -> Parsed {
+		let (pos, mut factors) = self.atom(pos)?;
+
+		let Some((pos, exponent)) = self.exponent(pos) else {
+			return Some((pos, factors));
+		};
+		for factor in &mut factors {
+			factor.exponent = factor.exponent.checked_mul(exponent)?;
+		}
+
+		Some((pos, factors))
+	}
+
+	fn atom(&self, pos: usize) -> Parsed {
+		if self.input.get(pos) == Some(&b'(') {
+			let (end, factors) = self.expression(pos + 1)?;
+			return (self.input.get(end) == Some(&b')')).then_some((end + 1, factors));
+		}
+
+		let (len, prefix, unit) = self.context.match_symbol(self.input.get(pos..)?)?;
+		Some((pos + len, vec![UnitFactor { prefix, unit, exponent: 1 }]))
+	}
+
+	fn separator(&self, pos: usize) -> Option<usize> {
+		let rest = self.input.get(pos..)?;
+
+		[b"*".as_slice(), DOT_OPERATOR_SIGN, MIDDLE_DOT_SIGN].into_iter().find(|sep| rest.starts_with(sep)).map(<[u8]>::len)
+	}
+
+	fn exponent(&self, pos: usize) -> Option<(usize, i8)> {
+		let rest = self.input.get(pos..)?;
+
+		let (len, value) = if let Some(after) = rest.strip_prefix(b"^") {
+			let negative = after.first() == Some(&b'-');
+			let (digits_len, value) = ascii_digits(&after[usize::from(negative)..])?;
+			(1 + usize::from(negative) + digits_len, if negative { -value } else { value })
+		} else if let Some(parsed) = ascii_digits(rest) {
+			parsed
+		} else {
+			let negative = rest.starts_with(SUP_MINUS_SIGN);
+			let sign_len = if negative { SUP_MINUS_SIGN.len() } else { 0 };
+			let (digits_len, value) = superscript_digits(&rest[sign_len..])?;
+			(sign_len + digits_len, if negative { -value } else { value })
+		};
+
+		(value != 0).then_some((pos + len, value))
+	}
+}
+
+fn ascii_digits(input: &[u8]) -> Option<(usize, i8)> {
+	let len = input.iter().take_while(|b| b.is_ascii_digit()).count();
+	let value = std::str::from_utf8(&input[..len]).ok()?.parse().ok()?;
+	Some((len, value))
+}
+
+fn superscript_digits(input: &[u8]) -> Option<(usize, i8)> {
+	let mut len = 0;
+	let mut value: i8 = 0;
+
+	while let Some((digit, sign)) = SUPERSCRIPT_DIGITS.iter().enumerate().find(|(_, sign)| input[len..].starts_with(sign)) {
+		value = value.checked_mul(10)?.checked_add(digit as i8)?;
+		len += sign.len();
+	}
+
+	(len > 0).then_some((len, value))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn base_units_compose_into_kinds() {
+		let ctx = UnitContext::new();
+
+		let joule = ctx.unit("kg⋅m²/s²");
+		assert_eq!(joule.kind, UnitKind::Energy);
+		assert_eq!(joule.dimension, Dimension::of(UnitKind::Energy));
+		assert!((joule.scale - 1.0).abs() < 1e-12);
+
+		assert_eq!(ctx.unit("N*m").kind, UnitKind::Torque);
+		assert_eq!(ctx.unit("J/mol*K").kind, UnitKind::MolarEntropy);
+	}
+
+	#[test]
+	fn groups_and_powers() {
+		let ctx = UnitContext::new();
+
+		let transfer = ctx.unit("W/(m2*K)");
+		assert_eq!(transfer.dimension, Dimension::of(UnitKind::Power) / Dimension::of(UnitKind::Area) / Dimension::of(UnitKind::Temperature));
+		assert!((ctx.unit("km2").scale - 1e6).abs() < 1e-6);
+		assert_eq!(ctx.unit("min").kind, UnitKind::Time);
+	}
+
+	#[test]
+	fn unknown_continuation_is_left() {
+		// `m/2` -> `m`
+		assert_eq!(UnitContext::new().parse_unit(b"m/2").map(|(len, _)| len), Some(1));
+	}
+}
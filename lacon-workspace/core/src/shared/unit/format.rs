@@ -0,0 +1,390 @@
+use super::prefixes::{PREFIX_NAMES, PREFIXES};
+use super::{CalcMode, Dimension, PrefixGroup, ResolvedUnit, UNITS, Uncertain, UnitContext, UnitFactor, UnitId, UnitKind, UnitPrefix};
+
+const SUPERSCRIPTS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Notation {
+	Prefixed,    // 15000 W -> 15kW
+	Engineering, // 15000 W -> 15×10³W
+	Plain,       // 15000 W -> 15000W
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FormatOptions {
+	pub significant_digits: Option<u8>,
+	pub notation: Notation,
+	/// `m²`, `⋅`, `×10³` вместо `m2`, `*`, `e3`
+	pub unicode: bool,
+	/// Для информации: `KiB` вместо `kB`
+	pub binary_prefixes: bool,
+	/// Полные названия на языке ("en", "ru"): `15 kilowatts`, `5 минут`. Только для одиночных юнитов
+	pub long_names: Option<&'static str>,
+}
+
+impl FormatOptions {
+	pub const DEFAULT: Self = Self {
+		significant_digits: None,
+		notation: Notation::Prefixed,
+		unicode: true,
+		binary_prefixes: false,
+		long_names: None,
+	};
+
+	pub const ASCII: Self = Self { unicode: false, ..Self::DEFAULT };
+}
+
+impl UnitContext {
+	/// Значение с юнитом: `format_quantity(15000.0, &W, ..)` = `15kW`
+	pub fn format_quantity(&self, value: f64, unit: &ResolvedUnit, options: &FormatOptions) -> String {
+		// Округляем до выбора префикса: 999.9996 W с тремя цифрами — `1.00kW`, а не `1000W`
+		let (value, factors, notation) = self.display_factors(round_significant(value, options.significant_digits), unit, options);
+
+		let number = format_number(value, &FormatOptions { notation, ..*options });
+		match self.long_name(&factors, value, options) {
+			Some(name) => format!("{} {}", number, name),
+			None => format!("{}{}", number, self.format_factors(&factors, options)),
+		}
+	}
+
+	/// `(9.81 ± 0.02)m/s²`: значение округляется до того же разряда, что и погрешность
+	/// (`significant_digits` — значащие цифры погрешности, по умолчанию 2)
+	pub fn format_uncertain(&self, quantity: Uncertain, unit: &ResolvedUnit, options: &FormatOptions) -> String {
+		let (value, factors, _) = self.display_factors(quantity.value, unit, options);
+		let ratio = if quantity.value == 0.0 { 1.0 } else { value / quantity.value };
+		let uncertainty = (quantity.uncertainty * ratio).abs();
+
+		let unit_text = match self.long_name(&factors, value, options) {
+			Some(name) => format!(" {}", name),
+			None => self.format_factors(&factors, options),
+		};
+		if uncertainty == 0.0 || !uncertainty.is_finite() {
+			return format!("{}{}", format_number(value, options), unit_text);
+		}
+
+		let digits = i32::from(options.significant_digits.unwrap_or(2).max(1));
+		let last_digit = uncertainty.log10().floor() as i32 - (digits - 1);
+		let decimals = usize::try_from(-last_digit).unwrap_or(0);
+		let round = |x: f64| (x / 10f64.powi(last_digit)).round() * 10f64.powi(last_digit);
+		let sign = if options.unicode { "±" } else { "+/-" };
+
+		format!("({:.*} {} {:.*}){}", decimals, round(value), sign, decimals, round(uncertainty), unit_text)
+	}
+
+	/// Юнит без значения; составной юнит с собственным именем сворачивается: `kg⋅m²/s²` -> `J`
+	pub fn format_unit(&self, unit: &ResolvedUnit, options: &FormatOptions) -> String {
+		let factors = match self.named_unit(unit) {
+			Some(factor) => vec![factor],
+			None => unit.factors.clone(),
+		};
+
+		self.long_name(&factors, 1.0, options).unwrap_or_else(|| self.format_factors(&factors, options))
+	}
+
+	/// Размерность в базовых единицах: `Energy` -> `m²⋅kg/s²`
+	pub fn format_dimension(&self, dimension: &Dimension, options: &FormatOptions) -> String {
+		render_parts(&self.dimension_exponents(dimension), options)
+	}
+
+	/// Ненулевые степени базовых величин по символам: `Energy` -> `[("m", 2), ("kg", 1), ("s", -2)]`
+	pub fn dimension_exponents(&self, dimension: &Dimension) -> Vec<(String, i8)> {
+		let symbols = Dimension::BASE_SYMBOLS.iter().map(|s| s.to_string()).chain(self.dimensions.iter().cloned());
+		symbols.zip(dimension.0).filter(|(_, e)| *e != 0).collect()
+	}
+
+	/// Встроенный юнит той же величины с префиксом, дающим тот же множитель: `kJ/s` -> `kW`, `g⋅m²/s²` -> `mJ`
+	pub fn named_unit(&self, unit: &ResolvedUnit) -> Option<UnitFactor> {
+		if unit.kind == UnitKind::None || matches!(unit.factors.as_slice(), [single] if single.exponent == 1) {
+			return None;
+		}
+
+		let same = |scale: f64| (scale - unit.scale).abs() <= unit.scale.abs() * 1e-9;
+		let candidates = || {
+			UNITS
+				.iter()
+				.enumerate()
+				.filter(|(_, def)| def.dimension == unit.kind && Dimension::of(def.dimension) == unit.dimension && def.props.offset == 0.0 && matches!(def.props.mode, CalcMode::Linear))
+		};
+
+		// Юнит с тем же множителем важнее префикса: `W⋅h` -> `Wh`, а не `3.6kJ`
+		let exact = candidates().find(|(_, def)| same(def.props.scale)).map(|(index, _)| UnitFactor {
+			prefix: None,
+			unit: UnitId(index as u16),
+			exponent: 1,
+		});
+		// Степень одного юнита (`m³`) уже читается как имя: префиксная замена (`kL`) только для составных
+		exact.or_else(|| {
+			if unit.factors.len() < 2 {
+				return None;
+			}
+			candidates().find_map(|(index, def)| {
+				let (symbol, factor, _) = PREFIXES.iter().find(|(_, factor, group)| def.prefix_group.accepts(*group) && same(def.props.scale * factor))?;
+				Some(UnitFactor {
+					prefix: Some(UnitPrefix { symbol, factor: *factor }),
+					unit: UnitId(index as u16),
+					exponent: 1,
+				})
+			})
+		})
+	}
+
+	// Множители для вывода: именованный юнит вместо составного и подобранный префикс.
+	// Возвращает новое значение и запись числа: вне диапазона префиксов — с порядком
+	fn display_factors(&self, value: f64, unit: &ResolvedUnit, options: &FormatOptions) -> (f64, Vec<UnitFactor>, Notation) {
+		let (mut value, mut factors) = match self.named_unit(unit) {
+			Some(factor) => (value, vec![factor]),
+			None => (value, unit.factors.clone()),
+		};
+		let mut notation = options.notation;
+
+		if options.notation == Notation::Prefixed
+			&& let [factor] = factors.as_mut_slice()
+			&& factor.exponent == 1
+		{
+			let (prefixed, clamped) = self.apply_best_prefix(value, factor, options);
+			value = prefixed;
+			if clamped {
+				notation = Notation::Engineering;
+			}
+		}
+
+		(value, factors, notation)
+	}
+
+	// Подбирает префикс так, чтобы мантисса попала в [1, 1000); возвращает новое значение
+	// и признак, что значение не уместилось даже в крайний префикс
+	fn apply_best_prefix(&self, value: f64, factor: &mut UnitFactor, options: &FormatOptions) -> (f64, bool) {
+		let Some(def) = self.definition(factor.unit) else {
+			return (value, false);
+		};
+		if def.props.offset != 0.0 || !matches!(def.props.mode, CalcMode::Linear) || !matches!(def.prefix_group, PrefixGroup::SI | PrefixGroup::Digital) || value == 0.0 || !value.is_finite() {
+			return (value, false);
+		}
+
+		let base = value * factor.prefix.map_or(1.0, |p| p.factor);
+		let binary = options.binary_prefixes && def.prefix_group == PrefixGroup::Digital;
+
+		// Только кратные тысяче: `da`, `h`, `d`, `c` в авто-выборе не участвуют
+		let candidates = PREFIXES.iter().filter(|(_, f, group)| match binary {
+			true => *group == PrefixGroup::Digital,
+			false => *group == PrefixGroup::SI && def.prefix_group.accepts(*group) && f.log10().round() as i32 % 3 == 0,
+		});
+		let choices: Vec<(Option<UnitPrefix>, f64)> = std::iter::once((None, 1.0)).chain(candidates.map(|(symbol, f, _)| (Some(UnitPrefix { symbol, factor: *f }), *f))).collect();
+
+		// Наибольший префикс, при котором мантисса не меньше 1; для совсем малых значений — наименьший
+		let (prefix, prefix_factor) = choices
+			.iter()
+			.filter(|(_, f)| (base / f).abs() >= 1.0)
+			.max_by(|a, b| a.1.total_cmp(&b.1))
+			.or_else(|| choices.iter().min_by(|a, b| a.1.total_cmp(&b.1)))
+			.copied()
+			.unwrap_or((None, 1.0));
+
+		let step = if binary { 1024.0 } else { 1000.0 };
+		let largest = choices.iter().map(|(_, f)| *f).fold(1.0, f64::max);
+		let mantissa = base / prefix_factor;
+
+		factor.prefix = prefix;
+		(mantissa, mantissa.abs() < 1.0 || (prefix_factor == largest && mantissa.abs() >= step))
+	}
+
+	// `kilowatts`, `километров`; составные юниты и юниты без названий пишутся символами
+	fn long_name(&self, factors: &[UnitFactor], count: f64, options: &FormatOptions) -> Option<String> {
+		let locale = options.long_names?;
+		let [factor] = factors else {
+			return None;
+		};
+		if factor.exponent != 1 {
+			return None;
+		}
+
+		let name = self.definition(factor.unit)?.names.form(locale, count)?;
+		let prefix = match factor.prefix {
+			Some(prefix) => {
+				let (_, english, russian) = PREFIX_NAMES.iter().find(|(symbol, ..)| *symbol == prefix.symbol)?;
+				if locale == "ru" { russian } else { english }
+			}
+			None => "",
+		};
+
+		Some(format!("{}{}", prefix, name))
+	}
+
+	fn format_factors(&self, factors: &[UnitFactor], options: &FormatOptions) -> String {
+		let parts: Vec<(String, i8)> = factors
+			.iter()
+			.map(|factor| {
+				let symbol = self.definition(factor.unit).map_or("?", |def| &def.symbol);
+				(format!("{}{}", factor.prefix.map_or("", |p| p.symbol), symbol), factor.exponent)
+			})
+			.collect();
+
+		render_parts(&parts, options)
+	}
+}
+
+// `a⋅b²/c⋅d` — всё после `/` уходит в знаменатель, как и при разборе
+fn render_parts(parts: &[(String, i8)], options: &FormatOptions) -> String {
+	let separator = if options.unicode { "⋅" } else { "*" };
+	let render = |items: Vec<String>| items.join(separator);
+
+	let num: Vec<String> = parts.iter().filter(|(_, e)| *e > 0).map(|(s, e)| format!("{}{}", s, exponent(*e, options))).collect();
+	let den: Vec<String> = parts.iter().filter(|(_, e)| *e < 0).map(|(s, e)| format!("{}{}", s, exponent(-*e, options))).collect();
+
+	match (num.is_empty(), den.is_empty()) {
+		(_, true) => render(num),
+		// Без числителя пишем отрицательные степени: `s⁻¹`
+		(true, false) => render(parts.iter().map(|(s, e)| format!("{}{}", s, exponent(*e, options))).collect()),
+		(false, false) => format!("{}/{}", render(num), render(den)),
+	}
+}
+
+fn exponent(value: i8, options: &FormatOptions) -> String {
+	match (value, options.unicode) {
+		(1, _) => String::new(),
+		(_, true) => exponent_digits(value.into()),
+		(_, false) if value < 0 => format!("^{}", value),
+		(_, false) => value.to_string(),
+	}
+}
+
+pub fn format_number(value: f64, options: &FormatOptions) -> String {
+	// Порядок выбирается по уже округлённому значению: 999.9996 с тремя цифрами — `1.00×10³`
+	let value = round_significant(value, options.significant_digits);
+
+	if options.notation == Notation::Engineering && value != 0.0 && value.is_finite() {
+		let power = ((value.abs().log10() / 3.0).floor() * 3.0) as i32;
+		let mantissa = render_significant(value / 10f64.powi(power), options.significant_digits);
+
+		return match (power, options.unicode) {
+			(0, _) => mantissa,
+			(_, true) => format!("{}×10{}", mantissa, exponent_digits(power)),
+			(_, false) => format!("{}e{}", mantissa, power),
+		};
+	}
+
+	render_significant(value, options.significant_digits)
+}
+
+fn exponent_digits(power: i32) -> String {
+	power.to_string().chars().map(|c| if c == '-' { '⁻' } else { SUPERSCRIPTS[c.to_digit(10).unwrap_or(0) as usize] }).collect()
+}
+
+// Без заданной точности округляем до 15 цифр, чтобы не выводить шум деления: `0.09999999999999999`
+fn round_significant(value: f64, digits: Option<u8>) -> f64 {
+	match value != 0.0 && value.is_finite() {
+		true => format!("{:.*e}", usize::from(digits.unwrap_or(15).max(1)) - 1, value).parse().unwrap_or(value),
+		false => value,
+	}
+}
+
+// Заданное число значащих цифр выводится вместе с нулями в конце: `1.00`
+fn render_significant(value: f64, digits: Option<u8>) -> String {
+	let value = round_significant(value, digits);
+	match digits {
+		Some(digits) if value != 0.0 && value.is_finite() => {
+			let decimals = i32::from(digits.max(1)) - 1 - value.abs().log10().floor() as i32;
+			format!("{:.*}", usize::try_from(decimals).unwrap_or(0), value)
+		}
+		_ => format!("{}", value),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn format(value: f64, unit: &str, options: &FormatOptions) -> String {
+		let ctx = UnitContext::new();
+		ctx.format_quantity(value, &ctx.unit(unit), options)
+	}
+
+	fn format_unit(unit: &str, options: &FormatOptions) -> String {
+		let ctx = UnitContext::new();
+		ctx.format_unit(&ctx.unit(unit), options)
+	}
+
+	#[test]
+	fn best_prefix_is_chosen() {
+		assert_eq!(format(15000.0, "W", &FormatOptions::DEFAULT), "15kW");
+		assert_eq!(format(0.0042, "km", &FormatOptions::DEFAULT), "4.2m");
+		assert_eq!(format(2.0, "kg⋅m²/s²", &FormatOptions::DEFAULT), "2J");
+	}
+
+	#[test]
+	fn unit_expressions_format() {
+		assert_eq!(format_unit("W/(m2*K)", &FormatOptions::DEFAULT), "W/m²⋅K");
+		assert_eq!(format_unit("W/(m2*K)", &FormatOptions::ASCII), "W/m2*K");
+		assert_eq!(format_unit("s^-1*m^-2", &FormatOptions::ASCII), "s^-1*m^-2");
+	}
+
+	#[test]
+	fn binary_prefixes() {
+		let binary = FormatOptions { binary_prefixes: true, ..FormatOptions::DEFAULT };
+		assert_eq!(format(2048.0, "Byte", &binary), "2KiByte");
+	}
+
+	#[test]
+	fn engineering_notation() {
+		let engineering = FormatOptions {
+			notation: Notation::Engineering,
+			significant_digits: Some(3),
+			..FormatOptions::DEFAULT
+		};
+		assert_eq!(format(123456.0, "m", &engineering), "123×10³m");
+		assert_eq!(format(123456.0, "m", &FormatOptions { unicode: false, ..engineering }), "123e3m");
+	}
+
+	#[test]
+	fn dimensions_format() {
+		assert_eq!(UnitContext::new().format_dimension(&Dimension::of(UnitKind::Energy), &FormatOptions::DEFAULT), "m²⋅kg/s²");
+	}
+
+	#[test]
+	fn english_long_names() {
+		let en = FormatOptions {
+			long_names: Some("en"),
+			..FormatOptions::DEFAULT
+		};
+		assert_eq!(format(15000.0, "W", &en), "15 kilowatts");
+		assert_eq!(format(1.0, "m", &en), "1 meter");
+		assert_eq!(format_unit("km/h", &en), "km/h");
+	}
+
+	#[test]
+	fn russian_plurals() {
+		let ru = FormatOptions {
+			long_names: Some("ru"),
+			..FormatOptions::DEFAULT
+		};
+		assert_eq!(format(5.0, "min", &ru), "5 минут");
+		assert_eq!(format(22.0, "min", &ru), "22 минуты");
+		assert_eq!(format(2000.0, "m", &ru), "2 километра");
+	}
+
+	#[test]
+	fn named_units_take_a_prefix() {
+		assert_eq!(format(2.5, "kJ/s", &FormatOptions::DEFAULT), "2.5kW");
+		assert_eq!(format(3.0, "g⋅m²/s²", &FormatOptions::DEFAULT), "3mJ");
+		assert_eq!(format(2.0, "kW⋅h", &FormatOptions::DEFAULT), "2kWh");
+		assert_eq!(format_unit("kJ/s", &FormatOptions::DEFAULT), "kW");
+		assert_eq!(format_unit("N*m", &FormatOptions::DEFAULT), "N⋅m");
+	}
+
+	#[test]
+	fn rounding_comes_before_prefix() {
+		let three = FormatOptions {
+			significant_digits: Some(3),
+			..FormatOptions::DEFAULT
+		};
+		assert_eq!(format(999.9996, "W", &three), "1.00kW");
+		assert_eq!(format(999.9996, "m", &FormatOptions { notation: Notation::Engineering, ..three }), "1.00×10³m");
+		assert_eq!(format(0.1 + 0.2, "m", &FormatOptions::DEFAULT), "300mm");
+	}
+
+	#[test]
+	fn prefixes_are_clamped() {
+		assert_eq!(format(1e-40, "m", &FormatOptions::DEFAULT), "100×10⁻¹²qm");
+		assert_eq!(format(2e40, "W", &FormatOptions::ASCII), "20e9QW");
+	}
+}
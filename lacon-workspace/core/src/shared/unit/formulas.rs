@@ -1,6 +1,18 @@
-#[derive(Debug, Clone)]
+use super::CalendarUnit;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
 pub enum CalcMode {
-	Linear,      // Обычная физика: 1 + 1 = 2
-	Exponential, // Инженерная физика: 1 * 2 = 2
-	Logarithmic, // dB, pH: сложение требует антилогарифмировани
+	Linear,                 // Обычная физика: 1 + 1 = 2
+	Exponential,            // Инженерная физика: 1 * 2 = 2
+	Logarithmic,            // dB, pH: сложение требует антилогарифмировани
+	Relative(RelativeTo),   // em, rem, px: масштаб задаёт вёрстка, см. RelativeContext
+	Calendar(CalendarUnit), // calmonth, calyear: длина зависит от даты, в секунды не переводится
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum RelativeTo {
+	FontSize,     // em
+	RootFontSize, // rem
+	Pixel,        // px = 1in / dpi
 }
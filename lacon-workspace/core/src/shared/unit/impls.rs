@@ -1,6 +1,13 @@
 use super::{UnitFormula, UnitKind};
 
 impl UnitKind {
+	/// Доли целого: в `+`/`-` действуют относительно другого операнда (`200W + 10%` = 220W).
+	/// Процентные пункты (`pp`) сюда не входят — они складываются с процентами как есть
+	pub fn is_ratio(&self) -> bool {
+		use UnitKind::*;
+		matches!(self, Fraction | Percent | Permille | PerTenThousand | PartPerMillion | PartPerBillion | PartPerTrillion)
+	}
+
 	pub fn formula(&self) -> UnitFormula {
 		use UnitKind::*;
 		match self {
@@ -29,10 +36,42 @@ impl UnitKind {
 				den: &[(Length, 1), (Time, 2)],
 			},
 			Density => UnitFormula { num: &[(Mass, 1)], den: &[(Length, 3)] },
+			AreaDensity => UnitFormula { num: &[(Mass, 1)], den: &[(Length, 2)] },
+			SpecificVolume => UnitFormula { num: &[(Length, 3)], den: &[(Mass, 1)] },
 			Energy => UnitFormula {
 				num: &[(Mass, 1), (Length, 2)],
 				den: &[(Time, 2)],
 			},
+			Torque => UnitFormula { num: &[(Force, 1), (Length, 1)], den: &[] },
+			DynamicViscosity => UnitFormula { num: &[(Pressure, 1), (Time, 1)], den: &[] },
+			KinematicViscosity => UnitFormula { num: &[(Length, 2)], den: &[(Time, 1)] },
+			// Стерадиан — квадрат радиана, поэтому `lm` = `cd⋅sr`, а не просто `cd`
+			SolidAngle => UnitFormula { num: &[(Angle, 2)], den: &[] },
+			AngularVelocity => UnitFormula { num: &[(Angle, 1)], den: &[(Time, 1)] },
+
+			// --- Вещество и тепло ---
+			MolarConcentration => UnitFormula {
+				num: &[(AmountOfSubstance, 1)],
+				den: &[(Length, 3)],
+			},
+			MolarVolume => UnitFormula {
+				num: &[(Length, 3)],
+				den: &[(AmountOfSubstance, 1)],
+			},
+			MolarMass => UnitFormula {
+				num: &[(Mass, 1)],
+				den: &[(AmountOfSubstance, 1)],
+			},
+			MolarEnergy => UnitFormula {
+				num: &[(Energy, 1)],
+				den: &[(AmountOfSubstance, 1)],
+			},
+			MolarEntropy => UnitFormula {
+				num: &[(Energy, 1)],
+				den: &[(AmountOfSubstance, 1), (Temperature, 1)],
+			},
+			Entropy => UnitFormula { num: &[(Energy, 1)], den: &[(Temperature, 1)] },
+			HeatCapacity => UnitFormula { num: &[(Energy, 1)], den: &[(Temperature, 1)] },
 
 			// --- Электричество ---
 			Power => UnitFormula {
@@ -60,13 +99,33 @@ impl UnitKind {
 				den: &[(Mass, 1), (Length, 2)],
 			},
 
+			MagneticFlux => UnitFormula {
+				num: &[(ElectricVoltage, 1), (Time, 1)],
+				den: &[],
+			},
+			MagneticFluxDensity => UnitFormula { num: &[(MagneticFlux, 1)], den: &[(Length, 2)] },
+			Inductance => UnitFormula {
+				num: &[(MagneticFlux, 1)],
+				den: &[(ElectricCurrent, 1)],
+			},
+
+			// --- Радиация и катализ ---
+			Activity => UnitFormula { num: &[(Scalar, 1)], den: &[(Time, 1)] },
+			AbsorbedDose => UnitFormula { num: &[(Energy, 1)], den: &[(Mass, 1)] },
+			EquivalentDose => UnitFormula { num: &[(Energy, 1)], den: &[(Mass, 1)] },
+			CatalyticActivity => UnitFormula {
+				num: &[(AmountOfSubstance, 1)],
+				den: &[(Time, 1)],
+			},
+
 			// --- Свет ---
-			LuminousFlux => UnitFormula { num: &[(LuminousIntensity, 1)], den: &[] },
-			Illuminance => UnitFormula {
-				num: &[(LuminousIntensity, 1)],
-				den: &[(Length, 2)],
+			LuminousFlux => UnitFormula {
+				num: &[(LuminousIntensity, 1), (SolidAngle, 1)],
+				den: &[],
 			},
+			Illuminance => UnitFormula { num: &[(LuminousFlux, 1)], den: &[(Length, 2)] },
 
+			InformationEntropy => UnitFormula { num: &[(Information, 1)], den: &[] },
 			BitRate => UnitFormula { num: &[(Information, 1)], den: &[(Time, 1)] },
 
 			DataDensity => UnitFormula { num: &[(Information, 1)], den: &[(Length, 2)] },
@@ -1,12 +1,14 @@
-use strum_macros::EnumIter;
+use serde::Serialize;
+use strum_macros::{EnumIter, EnumString};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, EnumString, Serialize)]
 pub enum UnitKind {
 	None,
 	// Безразмерные величины
 	Scalar,           // 1
 	Fraction,         // /   \\ FractionUnit
 	Percent,          // %   \\ Percentage
+	PercentagePoint,  // pp  \\ PercentagePoint
 	Permille,         // ‰  \\ Permile
 	PerTenThousand,   // ‱   \\ PerTenThousand
 	PartPerMillion,   // ppm \\ PartPerMillion
@@ -45,14 +47,19 @@ pub enum UnitKind {
 	Momentum,           // kg*m/s \\ Momentum
 
 	// Энергия, работа, сила
-	Energy,   // J  \\ EnergyUnit
-	Force,    // N  \\ ForceUnit
-	Pressure, // Pa  \\ PressureUnit
-	Power,    // W \\ ElectricPower
+	Energy,             // J  \\ EnergyUnit
+	Force,              // N  \\ ForceUnit
+	Pressure,           // Pa  \\ PressureUnit
+	Power,              // W \\ ElectricPower
+	DynamicViscosity,   // Pa⋅s \\ DynamicViscosity
+	KinematicViscosity, // St \\ KinematicViscosity
+	// Та же размерность, что у Energy: различаются по составу выражения (N⋅m, а не J)
+	Torque, // N⋅m \\ Torque
 
 	// Температура и тепло
-	Temperature, // K \\ TemperatureUnit
-	Entropy,     // J/K \\ Entropy
+	Temperature,  // K \\ TemperatureUnit
+	Entropy,      // J/K \\ Entropy
+	HeatCapacity, // J/K \\ HeatCapacity
 
 	// Электрические величины
 	ElectricVoltage,     // V \\ ElectricVoltage
@@ -61,6 +68,9 @@ pub enum UnitKind {
 	ElectricResistance,  // Ω \\ ElectricResistance
 	ElectricConductance, // S \\ ElectricConductance
 	ElectricCapacitance, // F \\ ElectricCapacitance
+	MagneticFlux,        // Wb \\ MagneticFlux
+	MagneticFluxDensity, // T \\ MagneticFluxDensity
+	Inductance,          // H \\ Inductance
 
 	// Световые величины
 	LuminousIntensity, // cd \\ LuminousIntensity
@@ -68,8 +78,15 @@ pub enum UnitKind {
 	Illuminance,       // lx \\ Illuminance
 
 	// Угловые величины
-	Degree, // deg \\ AngleDegree
-	Radian, // rad \\ AngleRadian
+	Angle,           // rad \\ PlaneAngle
+	SolidAngle,      // sr \\ SolidAngle
+	AngularVelocity, // rad/s \\ AngularVelocity
+
+	// Радиация и катализ
+	Activity,          // Bq \\ Radioactivity
+	AbsorbedDose,      // Gy \\ AbsorbedDose
+	EquivalentDose,    // Sv \\ EquivalentDose
+	CatalyticActivity, // kat \\ CatalyticActivity
 
 	// Информация
 	Information,        // B   \\ InformationSizeUnit
@@ -77,3 +94,41 @@ pub enum UnitKind {
 	DataDensity,        // B/m2 \\ DataDensity
 	BitRate,            // bps \\ BitRate
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::shared::{FormatOptions, UnitContext};
+
+	#[test]
+	fn torque_is_not_energy() {
+		let ctx = UnitContext::new();
+		let torque = ctx.unit("N⋅m");
+		assert_eq!(torque.kind, UnitKind::Torque);
+		assert_eq!(ctx.unit("kg⋅m²/s²").kind, UnitKind::Energy);
+		assert_eq!(torque.dimension, ctx.unit("J").dimension);
+		assert_eq!(ctx.convert(1.0, &torque, &ctx.unit("J")), None);
+		assert_eq!(ctx.convert(1.0, &ctx.unit("kN⋅m"), &torque), Some(1000.0));
+		assert_eq!(ctx.format_unit(&torque, &FormatOptions::DEFAULT), "N⋅m");
+	}
+
+	#[test]
+	fn magnetic_and_radiation_kinds() {
+		let ctx = UnitContext::new();
+		assert_eq!(ctx.unit("Wb/m2").kind, UnitKind::MagneticFluxDensity);
+		assert_eq!(ctx.convert(1.0, &ctx.unit("Wb/m2"), &ctx.unit("mT")), Some(1000.0));
+		assert_eq!(ctx.convert(1.0, &ctx.unit("Wb/A"), &ctx.unit("H")), Some(1.0));
+		assert_eq!(ctx.convert(1.0, &ctx.unit("Ci"), &ctx.unit("GBq")), Some(37.0));
+		assert_eq!(ctx.convert(1.0, &ctx.unit("Bq"), &ctx.unit("Hz")), None);
+		assert_eq!(ctx.convert(1.0, &ctx.unit("Sv"), &ctx.unit("Gy")), None);
+	}
+
+	#[test]
+	fn viscosity_and_catalytic_kinds() {
+		let ctx = UnitContext::new();
+		assert_eq!(ctx.convert(1.0, &ctx.unit("St"), &ctx.unit("m2/s")), Some(1e-4));
+		assert_eq!(ctx.unit("Pa⋅s").kind, UnitKind::DynamicViscosity);
+		assert_eq!(ctx.unit("mol/s").kind, UnitKind::CatalyticActivity);
+		assert!((ctx.convert(60.0, &ctx.unit("rpm"), &ctx.unit("rad/s")).unwrap() - std::f64::consts::TAU).abs() < 1e-12);
+	}
+}
@@ -4,17 +4,39 @@ mod definition;
 mod formulas;
 mod prefixes;
 
-mod arena;
+mod calendar;
+mod dimension;
+mod export;
+mod expression;
+mod format;
 mod impls;
 mod kind;
+mod percent;
+mod profile;
 mod props;
+mod rate;
+mod ratio;
+mod relative;
 mod structs;
+mod uncertain;
 mod units_declaration;
+mod user_units;
 
-pub use arena::*;
+pub use calendar::*;
 pub use definition::*;
+pub use dimension::*;
+pub use export::*;
+pub use expression::*;
+pub use format::*;
 pub use formulas::*;
 pub use kind::*;
+pub use percent::*;
+pub use profile::*;
 pub use props::*;
+pub use rate::*;
+pub use ratio::*;
+pub use relative::*;
 pub use structs::*;
+pub use uncertain::*;
 pub use units_declaration::*;
+pub use user_units::*;
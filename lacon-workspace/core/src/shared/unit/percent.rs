@@ -0,0 +1,111 @@
+use super::{FormatOptions, ResolvedUnit, UnitContext, UnitKind};
+use crate::shared::UnitError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PercentOp {
+	Add,
+	Sub,
+	Mul,
+	Div,
+}
+
+/// Число с юнитом: `(200.0, W)`
+pub type Operand<'u> = (f64, &'u ResolvedUnit);
+
+impl UnitContext {
+	/// Арифметика с долями (`%`, `‰`, `‱`, `ppm`, `ppb`, `ppt`) и процентными пунктами (`pp`):
+	///
+	/// ```text
+	/// 200W + 10%  = 220W     // доля справа — относительно левого операнда
+	/// 200W - 10%  = 180W
+	/// 5%   + 10%  = 5.5%     // тоже относительно: абсолютная прибавка пишется в пунктах
+	/// 5%   + 2pp  = 7%
+	/// 80km * 5%   = 4km      // умножение и деление просто масштабируют
+	/// 5W   / 0%   = inf W    // деление на ноль — как у f64
+	/// ```
+	///
+	/// Результат — в юните левого операнда (при `5% * 80km` — в юните правого)
+	pub fn percent_arithmetic(&self, lhs: Operand, op: PercentOp, rhs: Operand) -> Result<(f64, ResolvedUnit), UnitError> {
+		let (lhs_value, lhs_unit) = lhs;
+		let (rhs_value, rhs_unit) = rhs;
+
+		match (op, lhs_unit.kind, rhs_unit.kind) {
+			// Пункты прибавляются к процентам как есть, в процентах — как доля
+			(PercentOp::Add | PercentOp::Sub, lhs_kind, UnitKind::PercentagePoint) if lhs_kind.is_ratio() => {
+				let points = self.fraction(rhs_value, rhs_unit) / self.fraction(1.0, lhs_unit);
+				let value = if op == PercentOp::Add { lhs_value + points } else { lhs_value - points };
+				Ok((value, lhs_unit.clone()))
+			}
+			(PercentOp::Add | PercentOp::Sub, _, rhs_kind) if rhs_kind.is_ratio() => {
+				let delta = lhs_value * self.fraction(rhs_value, rhs_unit);
+				Ok((if op == PercentOp::Add { lhs_value + delta } else { lhs_value - delta }, lhs_unit.clone()))
+			}
+			(PercentOp::Mul, _, rhs_kind) if rhs_kind.is_ratio() => Ok((lhs_value * self.fraction(rhs_value, rhs_unit), lhs_unit.clone())),
+			(PercentOp::Mul, lhs_kind, _) if lhs_kind.is_ratio() => Ok((rhs_value * self.fraction(lhs_value, lhs_unit), rhs_unit.clone())),
+			(PercentOp::Div, _, rhs_kind) if rhs_kind.is_ratio() => Ok((lhs_value / self.fraction(rhs_value, rhs_unit), lhs_unit.clone())),
+			_ => Err(UnitError::IncompatibleUnits(self.format_unit(lhs_unit, &FormatOptions::DEFAULT), self.format_unit(rhs_unit, &FormatOptions::DEFAULT))),
+		}
+	}
+
+	/// Разность двух долей в процентных пунктах: `points_between(7%, 5%)` = 2pp
+	pub fn points_between(&self, lhs: Operand, rhs: Operand) -> Result<f64, UnitError> {
+		let (lhs_value, lhs_unit) = lhs;
+		let (rhs_value, rhs_unit) = rhs;
+		if !lhs_unit.kind.is_ratio() || !rhs_unit.kind.is_ratio() {
+			return Err(UnitError::IncompatibleUnits(self.format_unit(lhs_unit, &FormatOptions::DEFAULT), self.format_unit(rhs_unit, &FormatOptions::DEFAULT)));
+		}
+
+		Ok((self.fraction(lhs_value, lhs_unit) - self.fraction(rhs_value, rhs_unit)) * 100.0)
+	}
+
+	// Значение доли как числа: 10% -> 0.1. Через точный множитель: `10 / 100`, а не `10 * 0.01`
+	fn fraction(&self, value: f64, unit: &ResolvedUnit) -> f64 {
+		match self.exact_scale(unit) {
+			Some(scale) => value * scale.numer() as f64 / scale.denom() as f64,
+			None => value * unit.scale,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn apply(lhs: (f64, &str), op: PercentOp, rhs: (f64, &str)) -> Result<(f64, UnitKind), UnitError> {
+		let ctx = UnitContext::new();
+		ctx.percent_arithmetic((lhs.0, &ctx.unit(lhs.1)), op, (rhs.0, &ctx.unit(rhs.1))).map(|(value, unit)| (value, unit.kind))
+	}
+
+	#[test]
+	fn percent_of_a_quantity() {
+		assert_eq!(apply((200.0, "W"), PercentOp::Add, (10.0, "%")).unwrap().0, 220.0);
+		assert_eq!(apply((200.0, "W"), PercentOp::Sub, (10.0, "%")).unwrap().0, 180.0);
+		assert_eq!(apply((80.0, "km"), PercentOp::Mul, (5.0, "%")).unwrap(), (4.0, UnitKind::Length));
+		assert_eq!(apply((5.0, "%"), PercentOp::Mul, (80.0, "km")).unwrap(), (4.0, UnitKind::Length));
+	}
+
+	#[test]
+	fn division_by_zero_percent() {
+		assert_eq!(apply((200.0, "W"), PercentOp::Div, (50.0, "%")).unwrap(), (400.0, UnitKind::Power));
+		assert_eq!(apply((5.0, "W"), PercentOp::Div, (0.0, "%")).unwrap(), (f64::INFINITY, UnitKind::Power));
+		assert!(apply((0.0, "W"), PercentOp::Div, (0.0, "%")).unwrap().0.is_nan());
+	}
+
+	#[test]
+	fn percentage_points() {
+		assert_eq!(apply((5.0, "%"), PercentOp::Add, (2.0, "pp")).unwrap(), (7.0, UnitKind::Percent));
+		assert!((apply((5.0, "%"), PercentOp::Add, (10.0, "%")).unwrap().0 - 5.5).abs() < 1e-12);
+		assert!(apply((200.0, "W"), PercentOp::Add, (2.0, "pp")).is_err());
+
+		let ctx = UnitContext::new();
+		let percent = ctx.unit("%");
+		assert_eq!(ctx.points_between((7.0, &percent), (5.0, &percent)).ok().map(f64::round), Some(2.0));
+	}
+
+	#[test]
+	fn points_are_not_fractions() {
+		let ctx = UnitContext::new();
+		assert_eq!(ctx.convert(2500.0, &ctx.unit("ppm"), &ctx.unit("%")), Some(0.25));
+		assert_eq!(ctx.convert(1.0, &ctx.unit("pp"), &ctx.unit("%")), None);
+	}
+}
@@ -27,13 +27,48 @@ pub static PREFIXES: &[(&str, f64, PrefixGroup)] = &[
     ("y", 1e-24, PrefixGroup::SI), // иотто
     ("r", 1e-27, PrefixGroup::SI), // ронто
     ("q", 1e-30, PrefixGroup::SI), // квекто
-    // Температура
-    ("deg", 1.0, PrefixGroup::Thermal),
-    ("\u{00B0}", 1.0, PrefixGroup::Thermal),
     // Эти — только для PrefixGroup::Digital
     ("Ki", 1024.0, PrefixGroup::Digital),
     ("Mi", 1048576.0, PrefixGroup::Digital),
+    ("Gi", 1073741824.0, PrefixGroup::Digital),
+    ("Ti", 1099511627776.0, PrefixGroup::Digital),
+    ("Pi", 1125899906842624.0, PrefixGroup::Digital),
+    ("Ei", 1152921504606846976.0, PrefixGroup::Digital),
     // Для Metric можно выделить сокращенный список (например, без экзотики типа "атто" или "зепто")
     ("c", 1e-2, PrefixGroup::Metric), // санти-
     ("k", 1e3, PrefixGroup::Metric),  // кило-
 ];
+
+/// Полные названия префиксов: символ, английское, русское
+pub static PREFIX_NAMES: &[(&str, &str, &str)] = &[
+    ("Q", "quetta", "кветта"),
+    ("R", "ronna", "ронна"),
+    ("Y", "yotta", "иотта"),
+    ("Z", "zetta", "зетта"),
+    ("E", "exa", "экса"),
+    ("P", "peta", "пета"),
+    ("T", "tera", "тера"),
+    ("G", "giga", "гига"),
+    ("M", "mega", "мега"),
+    ("k", "kilo", "кило"),
+    ("h", "hecto", "гекто"),
+    ("da", "deca", "дека"),
+    ("d", "deci", "деци"),
+    ("c", "centi", "санти"),
+    ("m", "milli", "милли"),
+    ("μ", "micro", "микро"),
+    ("n", "nano", "нано"),
+    ("p", "pico", "пико"),
+    ("f", "femto", "фемто"),
+    ("a", "atto", "атто"),
+    ("z", "zepto", "зепто"),
+    ("y", "yocto", "иокто"),
+    ("r", "ronto", "ронто"),
+    ("q", "quecto", "квекто"),
+    ("Ki", "kibi", "киби"),
+    ("Mi", "mebi", "меби"),
+    ("Gi", "gibi", "гиби"),
+    ("Ti", "tebi", "теби"),
+    ("Pi", "pebi", "пеби"),
+    ("Ei", "exbi", "эксби"),
+];
@@ -0,0 +1,165 @@
+use super::{FormatOptions, Notation, ResolvedUnit, UNITS, UnitContext, UnitDef, UnitFactor, UnitId, UnitKind, UnitParser, UnitSystem};
+use crate::shared::UnitError;
+use std::borrow::Cow;
+
+/// Набор разрешённых юнитов для файла или схемы: `declare units SI`.
+/// Юниты `UnitSystem::Universal` разрешены всегда
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitProfile {
+	pub name: Cow<'static, str>,
+	pub systems: Vec<UnitSystem>,
+	/// Для величин, которых в `systems` нет вовсе: вольты и амперы в US customary берутся из SI
+	pub fallback: Vec<UnitSystem>,
+	/// Отдельные символы сверх систем: `custom("lab").allow("mi")`
+	pub allowed: Vec<String>,
+	/// Во что предлагать перевод; без записи — первый разрешённый юнит той же величины
+	pub preferred: Vec<(UnitKind, String)>,
+}
+
+impl UnitProfile {
+	pub fn si() -> Self {
+		Self::custom("SI").with_system(UnitSystem::SI)
+	}
+
+	/// SI вместе с допустимыми внесистемными и CGS: L, t, h, erg
+	pub fn metric() -> Self {
+		Self::custom("metric").with_system(UnitSystem::SI).with_system(UnitSystem::Metric).with_system(UnitSystem::CGS)
+	}
+
+	pub fn us_customary() -> Self {
+		Self::custom("US").with_system(UnitSystem::Customary).with_fallback(UnitSystem::SI).prefer(UnitKind::Temperature, "°F").prefer(UnitKind::Velocity, "mi/h")
+	}
+
+	/// Пустой профиль: только Universal и то, что добавлено явно
+	pub fn custom(name: impl Into<Cow<'static, str>>) -> Self {
+		Self {
+			name: name.into(),
+			systems: Vec::new(),
+			fallback: Vec::new(),
+			allowed: Vec::new(),
+			preferred: Vec::new(),
+		}
+	}
+
+	/// Встроенный профиль по имени из `declare units ...`
+	pub fn by_name(name: &str) -> Option<Self> {
+		match name.to_ascii_lowercase().as_str() {
+			"si" => Some(Self::si()),
+			"metric" => Some(Self::metric()),
+			"us" | "customary" | "imperial" => Some(Self::us_customary()),
+			_ => None,
+		}
+	}
+
+	pub fn with_system(mut self, system: UnitSystem) -> Self {
+		self.systems.push(system);
+		self
+	}
+
+	pub fn with_fallback(mut self, system: UnitSystem) -> Self {
+		self.fallback.push(system);
+		self
+	}
+
+	pub fn allow(mut self, symbol: impl Into<String>) -> Self {
+		self.allowed.push(symbol.into());
+		self
+	}
+
+	pub fn prefer(mut self, kind: UnitKind, unit: impl Into<String>) -> Self {
+		self.preferred.push((kind, unit.into()));
+		self
+	}
+
+	fn allows_def(&self, def: &UnitDef) -> bool {
+		def.system == UnitSystem::Universal || self.systems.contains(&def.system) || self.allowed.iter().any(|symbol| *symbol == def.symbol)
+	}
+}
+
+impl UnitContext {
+	/// Проверяет каждый множитель юнита; при нарушении предлагает перевод: `5ft` -> `1.524m`
+	pub fn check_profile(&self, value: f64, unit: &ResolvedUnit, profile: &UnitProfile) -> Result<(), UnitError> {
+		if unit.factors.iter().all(|factor| self.factor_allowed(factor, profile)) {
+			return Ok(());
+		}
+
+		let options = FormatOptions::DEFAULT;
+		let original = self.format_quantity(value, unit, &FormatOptions { notation: Notation::Plain, ..options });
+		let suggestion = self.suggest_in_profile(unit, profile).and_then(|target| Some(self.format_quantity(self.convert(value, unit, &target)?, &target, &options)));
+
+		Err(match suggestion {
+			Some(suggestion) => UnitError::OutsideProfileConvert(original, profile.name.to_string(), suggestion),
+			None => UnitError::OutsideProfile(original, profile.name.to_string()),
+		})
+	}
+
+	/// Тот же юнит, где запрещённые множители заменены разрешёнными: `ft/s` -> `m/s` в SI
+	pub fn suggest_in_profile(&self, unit: &ResolvedUnit, profile: &UnitProfile) -> Option<ResolvedUnit> {
+		if let Some((_, preferred)) = profile.preferred.iter().find(|(kind, _)| *kind == unit.kind) {
+			return self.resolve(preferred.as_bytes());
+		}
+
+		let mut factors = Vec::with_capacity(unit.factors.len());
+		for factor in &unit.factors {
+			if self.factor_allowed(factor, profile) {
+				factors.push(*factor);
+				continue;
+			}
+
+			let kind = self.definition(factor.unit)?.dimension;
+			match UNITS.iter().position(|def| def.dimension == kind && profile.allows_def(def)) {
+				Some(index) => factors.push(UnitFactor {
+					prefix: None,
+					unit: UnitId(index as u16),
+					exponent: factor.exponent,
+				}),
+				// Своего юнита у величины нет (`L` в SI) — раскладываем по базовым: `m3`
+				None => {
+					let coherent = self.resolve(self.format_dimension(&unit.dimension, &FormatOptions::ASCII).as_bytes())?;
+					return coherent.factors.iter().all(|factor| self.factor_allowed(factor, profile)).then_some(coherent);
+				}
+			}
+		}
+
+		UnitParser::new(self, b"").compose(factors)
+	}
+
+	fn factor_allowed(&self, factor: &UnitFactor, profile: &UnitProfile) -> bool {
+		let Some(def) = self.definition(factor.unit) else {
+			return true;
+		};
+
+		// Запасная система — только если основные эту величину не покрывают: вольты в US customary
+		profile.allows_def(def) || (profile.fallback.contains(&def.system) && !UNITS.iter().any(|other| other.dimension == def.dimension && other.system != UnitSystem::Universal && profile.allows_def(other)))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn profiles_accept_their_units() {
+		let ctx = UnitContext::new();
+		assert!(ctx.check_profile(5.0, &ctx.unit("km/s"), &UnitProfile::si()).is_ok());
+		assert!(ctx.check_profile(5.0, &ctx.unit("%"), &UnitProfile::si()).is_ok());
+		assert!(ctx.check_profile(5.0, &ctx.unit("L"), &UnitProfile::metric()).is_ok());
+		assert!(ctx.check_profile(5.0, &ctx.unit("V"), &UnitProfile::us_customary()).is_ok());
+		assert!(ctx.check_profile(1.0, &ctx.unit("erg"), &UnitProfile::custom("lab").with_system(UnitSystem::SI).allow("erg")).is_ok());
+	}
+
+	#[test]
+	fn outside_units_show_the_converted_value() {
+		let ctx = UnitContext::new();
+		assert!(matches!(ctx.check_profile(5.0, &ctx.unit("ft"), &UnitProfile::si()), Err(UnitError::OutsideProfileConvert(_, _, s)) if s == "1.524m"));
+		assert!(matches!(ctx.check_profile(2.0, &ctx.unit("L"), &UnitProfile::si()), Err(UnitError::OutsideProfileConvert(_, _, s)) if s == "0.002m³"));
+	}
+
+	#[test]
+	fn profiles_suggest_replacements() {
+		let ctx = UnitContext::new();
+		let suggested = ctx.suggest_in_profile(&ctx.unit("ft/s"), &UnitProfile::si());
+		assert_eq!(suggested.map(|unit| ctx.format_unit(&unit, &FormatOptions::DEFAULT)), Some("m/s".into()));
+		assert_eq!(ctx.suggest_in_profile(&ctx.unit("K"), &UnitProfile::us_customary()).map(|unit| unit.kind), Some(UnitKind::Temperature));
+	}
+}
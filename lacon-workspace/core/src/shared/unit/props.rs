@@ -1,4 +1,5 @@
 use super::CalcMode;
+use super::Ratio;
 use super::UnitKind;
 
 #[derive(Debug, Clone)]
@@ -18,6 +19,8 @@ pub struct UnitProps {
 	pub offset: f64,
 	pub exponent: f64,
 	pub mode: CalcMode,
+	/// Точное значение `scale`, если оно задано определением (`ft` = 0.3048 m ровно); целые `scale` точны и так
+	pub exact: Option<Ratio>,
 }
 
 impl UnitProps {
@@ -26,5 +29,6 @@ impl UnitProps {
 		offset: 0.0,
 		exponent: 1.0,
 		mode: CalcMode::Linear,
+		exact: None,
 	};
 }
@@ -0,0 +1,144 @@
+use super::{Dimension, FormatOptions, Operand, RelativeContext, ResolvedUnit, UNITS, UnitContext, UnitFactor, UnitId, UnitKind, UnitParser};
+use crate::shared::UnitError;
+use std::fmt;
+use std::str::FromStr;
+
+/// Сигнатура скорости из схемы: `<Energy> ÷ delta <Time>`, `<Mass> / Δ <Time>`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateSignature {
+	pub quantity: UnitKind,
+	pub per: UnitKind,
+}
+
+impl RateSignature {
+	pub const fn new(quantity: UnitKind, per: UnitKind) -> Self {
+		Self { quantity, per }
+	}
+
+	pub fn dimension(&self) -> Dimension {
+		Dimension::of(self.quantity) / Dimension::of(self.per)
+	}
+}
+
+impl FromStr for RateSignature {
+	type Err = UnitError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let invalid = || UnitError::InvalidRateSignature(s.to_string());
+		let (quantity, per) = s.split_once(['/', '÷']).ok_or_else(invalid)?;
+		let per = per.trim_start().strip_prefix("delta").or_else(|| per.trim_start().strip_prefix('Δ')).ok_or_else(invalid)?;
+		let kind = |part: &str| part.trim().strip_prefix('<').and_then(|p| p.strip_suffix('>')).and_then(|name| UnitKind::from_str(name.trim()).ok()).ok_or_else(invalid);
+
+		Ok(Self::new(kind(quantity)?, kind(per)?))
+	}
+}
+
+impl fmt::Display for RateSignature {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "<{:?}> ÷ delta <{:?}>", self.quantity, self.per)
+	}
+}
+
+impl UnitContext {
+	/// Количество за интервал: `150kJ / Δ 1min` = 2.5 kJ/s.
+	/// Знаменатель приводится к когерентному юниту своей размерности (s, m, K), интервал — разность, так что `Δ 5°C` = 5 K
+	pub fn rate(&self, quantity: Operand, interval: Operand) -> Result<(f64, ResolvedUnit), UnitError> {
+		let (value, unit) = quantity;
+		let (span, span_unit) = interval;
+		let options = FormatOptions::DEFAULT;
+
+		if span_unit.dimension.is_dimensionless() {
+			return Err(UnitError::InvalidInterval(self.format_quantity(span, span_unit, &options)));
+		}
+		let per = self.coherent_unit(&span_unit.dimension).ok_or_else(|| UnitError::InvalidInterval(self.format_quantity(span, span_unit, &options)))?;
+
+		let zero = self.convert_in(0.0, span_unit, &per, &RelativeContext::UNKNOWN)?;
+		let span = self.convert_in(span, span_unit, &per, &RelativeContext::UNKNOWN)? - zero;
+		if span == 0.0 {
+			return Err(UnitError::InvalidInterval(self.format_quantity(interval.0, span_unit, &options)));
+		}
+
+		let factors = unit
+			.factors
+			.iter()
+			.copied()
+			.chain(per.factors.iter().map(|factor| UnitFactor {
+				exponent: factor.exponent.saturating_neg(),
+				..*factor
+			}))
+			.collect();
+		let rate = UnitParser::new(self, b"")
+			.compose(factors)
+			.ok_or_else(|| UnitError::IncompatibleUnits(self.format_unit(unit, &options), self.format_unit(&per, &options)))?;
+
+		Ok((value / span, rate))
+	}
+
+	/// Когерентный юнит размерности: `m/s²`, `kg⋅m²/s²`; для пользовательской величины — её юнит с множителем 1 (`cell`)
+	pub fn coherent_unit(&self, dimension: &Dimension) -> Option<ResolvedUnit> {
+		let mut factors = Vec::new();
+		for (slot, exponent) in dimension.0.iter().enumerate().filter(|(_, exponent)| **exponent != 0) {
+			let base = match Dimension::BASE_SYMBOLS.get(slot) {
+				Some(symbol) => *self.resolve(symbol.as_bytes())?.factors.first()?,
+				None => {
+					let custom = Dimension::custom(slot - Dimension::BASE_SYMBOLS.len())?;
+					let index = self.user_units.iter().position(|unit| unit.dimension == custom && unit.def.props.scale == 1.0 && unit.def.props.offset == 0.0)?;
+					UnitFactor {
+						prefix: None,
+						unit: UnitId(u16::try_from(UNITS.len() + index).ok()?),
+						exponent: 1,
+					}
+				}
+			};
+			factors.push(UnitFactor { exponent: *exponent, ..base });
+		}
+
+		UnitParser::new(self, b"").compose(factors)
+	}
+
+	/// Совпадает ли размерность скорости с сигнатурой схемы: `kg/h` подходит к `<Mass> ÷ delta <Time>`
+	pub fn check_rate(&self, unit: &ResolvedUnit, signature: &RateSignature) -> Result<(), UnitError> {
+		match unit.dimension == signature.dimension() {
+			true => Ok(()),
+			false => Err(UnitError::RateMismatch(self.format_unit(unit, &FormatOptions::DEFAULT), signature.to_string())),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::shared::{PrefixGroup, UnitBase, UnitDeclaration};
+
+	#[test]
+	fn rates_use_coherent_units() {
+		let ctx = UnitContext::new();
+		let (value, rate) = ctx.rate((150.0, &ctx.unit("kJ")), (1.0, &ctx.unit("min"))).expect("kJ per min");
+		assert!((value - 2.5).abs() < 1e-12);
+		assert_eq!((rate.kind, ctx.format_unit(&rate, &FormatOptions::DEFAULT)), (UnitKind::Power, "kW".to_string()));
+		assert!(ctx.rate((1.0, &ctx.unit("K")), (0.0, &ctx.unit("h"))).is_err());
+
+		let (per_kelvin, _) = ctx.rate((10.0, &ctx.unit("J")), (5.0, &ctx.unit("\u{00B0}C"))).expect("J per °C");
+		assert!((per_kelvin - 2.0).abs() < 1e-12);
+	}
+
+	#[test]
+	fn custom_dimensions_rate_per_their_unit() {
+		// Знаменатель — собственный юнит величины, а не имя размерности
+		let mut ctx = UnitContext::new();
+		ctx.declare(UnitDeclaration::new("cell", UnitBase::Dimension("Cell".into())).with_prefixes(PrefixGroup::SI)).expect("cell");
+		let (per_cell, rate) = ctx.rate((10.0, &ctx.unit("kg")), (2.0, &ctx.unit("kcell"))).expect("kg per kcell");
+		assert!((per_cell - 0.005).abs() < 1e-12);
+		assert_eq!(ctx.format_unit(&rate, &FormatOptions::DEFAULT), "kg/cell");
+	}
+
+	#[test]
+	fn signatures_check_rates() {
+		let ctx = UnitContext::new();
+		let signature: RateSignature = "<Mass> ÷ Δ <Time>".parse().expect("signature");
+		assert_eq!(signature, RateSignature::new(UnitKind::Mass, UnitKind::Time));
+		assert!(ctx.check_rate(&ctx.unit("kg/h"), &signature).is_ok());
+		assert!(ctx.check_rate(&ctx.unit("J/s"), &signature).is_err());
+		assert!("<Mass> / <Time>".parse::<RateSignature>().is_err());
+	}
+}
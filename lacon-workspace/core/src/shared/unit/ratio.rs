@@ -0,0 +1,254 @@
+use super::{CalcMode, ResolvedUnit, UnitContext};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// Точная дробь для множителей, заданных определением: `ft` = 3048/10000 m, `KiByte` = 8192 bit.
+/// Всегда несократима и со знаменателем > 0, так что `==` сравнивает значения
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Ratio {
+	num: i128,
+	den: i128,
+}
+
+impl Ratio {
+	pub const ONE: Self = Self { num: 1, den: 1 };
+
+	/// Сокращает дробь; для таблиц юнитов, где знаменатель заведомо не ноль
+	pub const fn new(num: i128, den: i128) -> Self {
+		match Self::try_new(num, den) {
+			Some(ratio) => ratio,
+			None => panic!("Ratio with zero denominator"),
+		}
+	}
+
+	/// Сокращает дробь; `None` для нулевого знаменателя
+	pub const fn try_new(num: i128, den: i128) -> Option<Self> {
+		if den == 0 {
+			return None;
+		}
+		let divisor = gcd(num.unsigned_abs(), den.unsigned_abs()) as i128;
+		let sign = if den < 0 { -1 } else { 1 };
+		Some(Self {
+			num: sign * num / divisor,
+			den: sign * den / divisor,
+		})
+	}
+
+	pub const fn numer(&self) -> i128 {
+		self.num
+	}
+
+	/// Всегда больше нуля
+	pub const fn denom(&self) -> i128 {
+		self.den
+	}
+
+	pub const fn integer(value: i128) -> Self {
+		Self { num: value, den: 1 }
+	}
+
+	/// `10^exponent`, если помещается в i128
+	pub fn pow10(exponent: i32) -> Option<Self> {
+		let power = 10i128.checked_pow(exponent.unsigned_abs())?;
+		Some(if exponent < 0 { Self { num: 1, den: power } } else { Self::integer(power) })
+	}
+
+	pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+		// Перекрёстное сокращение до умножения отодвигает переполнение
+		let a = gcd(self.num.unsigned_abs(), rhs.den.unsigned_abs()).max(1) as i128;
+		let b = gcd(rhs.num.unsigned_abs(), self.den.unsigned_abs()).max(1) as i128;
+		Self::try_new((self.num / a).checked_mul(rhs.num / b)?, (self.den / b).checked_mul(rhs.den / a)?)
+	}
+
+	pub fn checked_div(self, rhs: Self) -> Option<Self> {
+		self.checked_mul(rhs.recip()?)
+	}
+
+	pub fn checked_add(self, rhs: Self) -> Option<Self> {
+		let den = self.den.checked_mul(rhs.den)?;
+		Self::try_new(self.num.checked_mul(rhs.den)?.checked_add(rhs.num.checked_mul(self.den)?)?, den)
+	}
+
+	pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+		self.checked_add(Self { num: rhs.num.checked_neg()?, ..rhs })
+	}
+
+	pub fn checked_powi(self, exponent: i32) -> Option<Self> {
+		let base = if exponent < 0 { self.recip()? } else { self };
+		(0..exponent.unsigned_abs()).try_fold(Self::ONE, |acc, _| acc.checked_mul(base))
+	}
+
+	pub fn recip(self) -> Option<Self> {
+		Self::try_new(self.den, self.num)
+	}
+
+	/// Точное сравнение; `None`, если перекрёстное произведение не помещается в i128
+	pub fn checked_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.num.checked_mul(other.den)?.cmp(&other.num.checked_mul(self.den)?))
+	}
+
+	/// Единственное место, где точность теряется
+	pub fn to_f64(self) -> f64 {
+		self.num as f64 / self.den as f64
+	}
+
+	/// Конечная десятичная запись: знаменатель раскладывается только на 2 и 5
+	pub fn is_decimal(&self) -> bool {
+		let mut den = self.den;
+		for factor in [2, 5] {
+			while den % factor == 0 {
+				den /= factor;
+			}
+		}
+		den == 1
+	}
+}
+
+const fn gcd(mut a: u128, mut b: u128) -> u128 {
+	while b != 0 {
+		(a, b) = (b, a % b);
+	}
+	a
+}
+
+impl From<i128> for Ratio {
+	fn from(value: i128) -> Self {
+		Self::integer(value)
+	}
+}
+
+impl FromStr for Ratio {
+	type Err = ();
+
+	/// Литерал как написан, без округления до f64: `0.1`, `1_000.25`, `6.02e23`, `1/3`
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let s = s.replace('_', "");
+		if let Some((num, den)) = s.split_once('/') {
+			let (num, den): (Self, Self) = (num.parse()?, den.parse()?);
+			return Self::try_new(num.num.checked_mul(den.den).ok_or(())?, den.num.checked_mul(num.den).ok_or(())?).ok_or(());
+		}
+
+		let (mantissa, exponent) = match s.split_once(['e', 'E']) {
+			Some((mantissa, exponent)) => (mantissa, exponent.parse::<i32>().map_err(|_| ())?),
+			None => (s.as_str(), 0),
+		};
+		let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+		if whole.is_empty() && fraction.is_empty() {
+			return Err(());
+		}
+
+		let digits: i128 = format!("{}{}", whole, fraction).parse().map_err(|_| ())?;
+		let scale = Self::pow10(exponent.checked_sub(i32::try_from(fraction.len()).map_err(|_| ())?).ok_or(())?).ok_or(())?;
+		Self::integer(digits).checked_mul(scale).ok_or(())
+	}
+}
+
+impl fmt::Display for Ratio {
+	/// Конечные дроби — десятичной записью (`0.3048`), остальные — через `/` (`1/3`)
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.den == 1 {
+			return write!(f, "{}", self.num);
+		}
+		if !self.is_decimal() {
+			return write!(f, "{}/{}", self.num, self.den);
+		}
+
+		let mut decimals = 0;
+		let mut scaled = *self;
+		while scaled.den != 1 {
+			match scaled.checked_mul(Self::integer(10)) {
+				Some(next) => scaled = next,
+				None => return write!(f, "{}/{}", self.num, self.den),
+			}
+			decimals += 1;
+		}
+
+		let sign = if scaled.num < 0 { "-" } else { "" };
+		let digits = format!("{:0>width$}", scaled.num.unsigned_abs(), width = decimals + 1);
+		let (whole, fraction) = digits.split_at(digits.len() - decimals);
+		write!(f, "{}{}.{}", sign, whole, fraction)
+	}
+}
+
+impl UnitContext {
+	/// Точный множитель до базовых единиц, если все юниты и префиксы заданы точно
+	pub fn exact_scale(&self, unit: &ResolvedUnit) -> Option<Ratio> {
+		unit.factors.iter().try_fold(Ratio::ONE, |scale, factor| {
+			let def = self.definition(factor.unit)?;
+			if def.props.offset != 0.0 || !matches!(def.props.mode, CalcMode::Linear) {
+				return None;
+			}
+
+			// Префиксы записаны конечными десятичными литералами (`1e-3`, `1024`), их запись и есть точное значение
+			let prefix = match factor.prefix {
+				Some(prefix) => prefix.factor.to_string().parse().ok()?,
+				None => Ratio::ONE,
+			};
+			let exact = match def.props.exact {
+				Some(exact) => exact,
+				None if def.props.scale.fract() == 0.0 && def.props.scale.abs() < 2f64.powi(53) => Ratio::integer(def.props.scale as i128),
+				None => return None,
+			};
+
+			scale.checked_mul(prefix.checked_mul(exact)?.checked_powi(i32::from(factor.exponent))?)
+		})
+	}
+
+	/// Точный перевод: `convert_exact("0.1".parse()?, &kL, &L)` = 100 ровно; `None`, если хоть один множитель не точный
+	pub fn convert_exact(&self, value: Ratio, from: &ResolvedUnit, to: &ResolvedUnit) -> Option<Ratio> {
+		if !from.is_compatible(to) || self.is_calendar(from) || self.is_calendar(to) {
+			return None;
+		}
+
+		value.checked_mul(self.exact_scale(from)?.checked_div(self.exact_scale(to)?)?)
+	}
+
+	/// Точное сравнение величин: `1ft == 12in`, хотя в f64 получается 0.30479999999999996 m
+	pub fn compare_exact(&self, lhs: (Ratio, &ResolvedUnit), rhs: (Ratio, &ResolvedUnit)) -> Option<Ordering> {
+		lhs.0.checked_cmp(&self.convert_exact(rhs.0, rhs.1, lhs.1)?)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::shared::{UnitBase, UnitDeclaration};
+
+	fn ratio(s: &str) -> Ratio {
+		s.parse().expect(s)
+	}
+
+	#[test]
+	fn literals_stay_irreducible() {
+		assert_eq!(ratio("1_000.25").to_string(), "1000.25");
+		assert_eq!(ratio("6.5e-3"), Ratio::new(13, 2000));
+		assert_eq!("1/0".parse::<Ratio>(), Err(()));
+		assert_eq!(Ratio::try_new(2, -4).map(|r| (r.numer(), r.denom())), Some((-1, 2)));
+		assert_eq!(ratio("0.1").checked_add(ratio("0.2")), Some(ratio("0.3")));
+	}
+
+	#[test]
+	fn definitions_convert_exactly() {
+		let ctx = UnitContext::new();
+		let exact = |value: Ratio, from: &str, to: &str| ctx.convert_exact(value, &ctx.unit(from), &ctx.unit(to));
+
+		assert_eq!(ctx.convert(1.0, &ctx.unit("ft"), &ctx.unit("in")), Some(12.0));
+		assert_eq!(ctx.convert(0.1, &ctx.unit("kL"), &ctx.unit("L")), Some(100.0));
+		assert_eq!(exact(ratio("0.1"), "kL", "L"), Some(Ratio::integer(100)));
+		assert_eq!(exact(Ratio::ONE, "lb", "g"), Some(ratio("453.59237")));
+		assert_eq!(exact(Ratio::ONE, "KiByte", "bit"), Some(Ratio::integer(8192)));
+		assert_eq!(exact(Ratio::ONE, "h", "min"), Some(Ratio::integer(60)));
+		assert_eq!(exact(Ratio::ONE, "pt", "in").map(|r| r.to_string()), Some("1/72".into()));
+		// Сдвиг нуля и π точными не бывают
+		assert_eq!(exact(Ratio::ONE, "°C", "K"), None);
+		assert_eq!(exact(Ratio::ONE, "deg", "rad"), None);
+	}
+
+	#[test]
+	fn declared_units_convert_exactly() {
+		let mut ctx = UnitContext::new();
+		ctx.declare(UnitDeclaration::new("tick", UnitBase::Unit("s".into())).with_scale(0.05)).unwrap();
+		assert_eq!(ctx.convert_exact(Ratio::integer(3), &ctx.unit("tick"), &ctx.unit("s")), Some(ratio("0.15")));
+	}
+}
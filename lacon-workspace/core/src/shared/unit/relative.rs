@@ -0,0 +1,70 @@
+use super::RelativeTo;
+
+const INCH: f64 = 0.0254;
+
+/// Параметры вёрстки для em/rem/px; размеры шрифтов — в метрах
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RelativeContext {
+	pub font_size: Option<f64>,
+	pub root_font_size: Option<f64>,
+	pub dpi: Option<f64>,
+}
+
+impl RelativeContext {
+	/// Вёрстка неизвестна: относительные юниты остаются как есть
+	pub const UNKNOWN: Self = Self {
+		font_size: None,
+		root_font_size: None,
+		dpi: None,
+	};
+
+	/// Умолчания браузера: 96 dpi, кегль 16px
+	pub const CSS: Self = Self {
+		font_size: Some(16.0 * INCH / 96.0),
+		root_font_size: Some(16.0 * INCH / 96.0),
+		dpi: Some(96.0),
+	};
+
+	/// Сколько метров в одной единице базы
+	pub fn base(&self, relative: RelativeTo) -> Option<f64> {
+		match relative {
+			RelativeTo::FontSize => self.font_size,
+			RelativeTo::RootFontSize => self.root_font_size,
+			RelativeTo::Pixel => self.dpi.map(|dpi| INCH / dpi),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::shared::{UnitContext, UnitError};
+
+	#[test]
+	fn typographic_units_are_absolute() {
+		let ctx = UnitContext::new();
+		assert!((ctx.convert(72.0, &ctx.unit("pt"), &ctx.unit("in")).unwrap() - 1.0).abs() < 1e-12);
+		assert!((ctx.convert(1.0, &ctx.unit("pc"), &ctx.unit("pt")).unwrap() - 12.0).abs() < 1e-12);
+	}
+
+	#[test]
+	fn relative_units_wait_for_layout() {
+		let ctx = UnitContext::new();
+		let em = ctx.unit("em");
+		assert!(matches!(ctx.convert_in(1.0, &em, &ctx.unit("m"), &RelativeContext::UNKNOWN), Err(UnitError::Deferred(_))));
+		assert_eq!(ctx.convert(2.0, &em, &em), Some(2.0));
+	}
+
+	#[test]
+	fn layout_resolves_relative_units() {
+		let ctx = UnitContext::new();
+		let layout = RelativeContext {
+			font_size: Some(INCH / 96.0 * 20.0),
+			..RelativeContext::CSS
+		};
+		let px = ctx.unit("px");
+		assert!((ctx.convert_in(1.5, &ctx.unit("em"), &px, &layout).unwrap() - 30.0).abs() < 1e-9);
+		assert!((ctx.convert_in(2.0, &ctx.unit("rem"), &px, &layout).unwrap() - 32.0).abs() < 1e-9);
+		assert!((ctx.convert_in(96.0, &px, &ctx.unit("in"), &layout).unwrap() - 1.0).abs() < 1e-12);
+	}
+}
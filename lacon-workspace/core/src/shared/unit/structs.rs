@@ -1,5 +1,9 @@
 use super::CalcMode;
+use super::Dimension;
 use super::UnitKind;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
 
 pub struct UnitFormula {
 	pub num: &'static [(UnitKind, i8)],
@@ -17,3 +21,100 @@ impl UnitData {
 		mode: CalcMode::Linear,
 	};
 }
+
+/// Индекс определения в реестре юнитов (`UNITS`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct UnitId(pub u16);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnitPrefix {
+	pub symbol: &'static str,
+	pub factor: f64,
+}
+
+/// Один множитель юнит-выражения: `km²` = префикс `k`, юнит `m`, степень 2
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnitFactor {
+	pub prefix: Option<UnitPrefix>,
+	pub unit: UnitId,
+	pub exponent: i8,
+}
+
+/// Разобранный суффикс числа: `km/h`, `kg⋅m²/s²`, `W/(m2*K)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedUnit {
+	pub factors: Vec<UnitFactor>,
+	pub dimension: Dimension,
+	/// Именованная величина (`Velocity`, `Energy`) или `UnitKind::None`, если такой нет
+	pub kind: UnitKind,
+	/// Величина угадана по размерности или по формуле, общей для нескольких величин (`s⁻¹`, `J/kg`)
+	pub inferred: bool,
+	/// Полный множитель перевода в базовые единицы (префиксы и степени уже учтены).
+	/// Для em/rem/px база вёрстки сюда не входит — переводить через `UnitContext::convert_in`
+	pub scale: f64,
+}
+
+impl ResolvedUnit {
+	/// Переводимы ли юниты друг в друга. Одна размерность ещё не одна величина: N⋅m не переводится в J, Bq — в Hz, Sv — в Gy.
+	/// Угаданная величина не мешает переводу: `J/kg -> Sv`, `s⁻¹ -> Bq`.
+	/// Безразмерные доли переводятся между собой (`ppm -> %`), но не в процентные пункты
+	pub fn is_compatible(&self, other: &Self) -> bool {
+		let named = |unit: &Self| unit.kind != UnitKind::None && !unit.inferred;
+		let distinct = !self.dimension.is_dimensionless() || self.kind == UnitKind::PercentagePoint || other.kind == UnitKind::PercentagePoint;
+		let different_kinds = self.kind != other.kind && named(self) && named(other) && distinct;
+		self.dimension == other.dimension && !different_kinds
+	}
+
+	/// Та же единица как другая величина той же размерности: `J/K` как HeatCapacity, `s⁻¹` как Activity
+	pub fn with_kind(self, kind: UnitKind) -> Option<Self> {
+		(Dimension::of(kind) == self.dimension).then_some(Self { kind, inferred: false, ..self })
+	}
+}
+
+/// Юнит на токене. Общий для всех вхождений одного написания в файле: токен не аллоцирует,
+/// а в `Debug` (и в дампах токенов) виден только вид величины
+#[derive(Clone, PartialEq)]
+pub struct UnitRef(Arc<ResolvedUnit>);
+
+impl From<ResolvedUnit> for UnitRef {
+	fn from(unit: ResolvedUnit) -> Self {
+		Self(Arc::new(unit))
+	}
+}
+
+impl Deref for UnitRef {
+	type Target = ResolvedUnit;
+
+	fn deref(&self) -> &ResolvedUnit {
+		&self.0
+	}
+}
+
+impl fmt::Debug for UnitRef {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{:?}", self.0.kind)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::shared::UnitContext;
+
+	#[test]
+	fn inferred_kinds_convert_within_dimension() {
+		let ctx = UnitContext::new();
+		assert!(ctx.unit("J/kg").inferred && !ctx.unit("N⋅m").inferred);
+		assert_eq!(ctx.convert(1.0, &ctx.unit("Sv"), &ctx.unit("J/kg")), Some(1.0));
+		assert_eq!(ctx.convert(2.0, &ctx.unit("J/kg"), &ctx.unit("mGy")), Some(2000.0));
+		assert_eq!(ctx.convert(1.0, &ctx.unit("Bq"), &ctx.unit("s^-1")), Some(1.0));
+	}
+
+	#[test]
+	fn kinds_change_within_dimension() {
+		let entropy = UnitContext::new().unit("J/K");
+		assert_eq!(entropy.kind, UnitKind::Entropy);
+		assert_eq!(entropy.clone().with_kind(UnitKind::HeatCapacity).map(|unit| unit.kind), Some(UnitKind::HeatCapacity));
+		assert_eq!(entropy.with_kind(UnitKind::Torque), None);
+	}
+}
@@ -0,0 +1,167 @@
+use super::{RelativeContext, ResolvedUnit, UnitContext};
+use crate::shared::UnitError;
+use std::cmp::Ordering;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// Значение со стандартной неопределённостью: `9.81 m/s2 ± 0.02 m/s2`.
+/// Погрешности операндов считаются независимыми и складываются в первом приближении
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Uncertain {
+	pub value: f64,
+	pub uncertainty: f64,
+}
+
+impl Uncertain {
+	pub fn new(value: f64, uncertainty: f64) -> Self {
+		Self { value, uncertainty: uncertainty.abs() }
+	}
+
+	pub fn exact(value: f64) -> Self {
+		Self { value, uncertainty: 0.0 }
+	}
+
+	/// Относительная погрешность: `relative(200.0, 0.02)` = 200 ± 4
+	pub fn relative(value: f64, fraction: f64) -> Self {
+		Self::new(value, value * fraction)
+	}
+
+	pub fn relative_uncertainty(&self) -> f64 {
+		self.uncertainty / self.value.abs()
+	}
+
+	pub fn powi(self, exponent: i32) -> Self {
+		Self::new(self.value.powi(exponent), f64::from(exponent) * self.value.powi(exponent - 1) * self.uncertainty)
+	}
+
+	pub fn powf(self, exponent: f64) -> Self {
+		Self::new(self.value.powf(exponent), exponent * self.value.powf(exponent - 1.0) * self.uncertainty)
+	}
+
+	/// Сравнение с учётом погрешности: разница в пределах `coverage` неопределённостей — `Equal`
+	pub fn compare(&self, other: &Self, coverage: f64) -> Ordering {
+		let difference = *self - *other;
+		match difference.value.abs() <= coverage * difference.uncertainty {
+			true => Ordering::Equal,
+			false => difference.value.total_cmp(&0.0),
+		}
+	}
+}
+
+impl From<f64> for Uncertain {
+	fn from(value: f64) -> Self {
+		Self::exact(value)
+	}
+}
+
+impl Neg for Uncertain {
+	type Output = Self;
+
+	fn neg(self) -> Self {
+		Self { value: -self.value, ..self }
+	}
+}
+
+impl Add for Uncertain {
+	type Output = Self;
+
+	fn add(self, rhs: Self) -> Self {
+		Self::new(self.value + rhs.value, self.uncertainty.hypot(rhs.uncertainty))
+	}
+}
+
+impl Sub for Uncertain {
+	type Output = Self;
+
+	fn sub(self, rhs: Self) -> Self {
+		Self::new(self.value - rhs.value, self.uncertainty.hypot(rhs.uncertainty))
+	}
+}
+
+impl Mul for Uncertain {
+	type Output = Self;
+
+	fn mul(self, rhs: Self) -> Self {
+		Self::new(self.value * rhs.value, (rhs.value * self.uncertainty).hypot(self.value * rhs.uncertainty))
+	}
+}
+
+impl Div for Uncertain {
+	type Output = Self;
+
+	fn div(self, rhs: Self) -> Self {
+		let value = self.value / rhs.value;
+		Self::new(value, (self.uncertainty / rhs.value).hypot(value * rhs.uncertainty / rhs.value))
+	}
+}
+
+impl UnitContext {
+	/// `value unit ± uncertainty uncertainty_unit`. Безразмерная погрешность при размерном значении — относительная: `200W ± 2%`
+	pub fn uncertain(&self, value: f64, unit: &ResolvedUnit, uncertainty: f64, uncertainty_unit: Option<&ResolvedUnit>) -> Result<Uncertain, UnitError> {
+		let Some(uncertainty_unit) = uncertainty_unit else {
+			return Ok(Uncertain::new(value, uncertainty));
+		};
+
+		if uncertainty_unit.dimension.is_dimensionless() && !unit.dimension.is_dimensionless() {
+			return Ok(Uncertain::relative(value, uncertainty * uncertainty_unit.scale));
+		}
+
+		// Погрешность — разность, смещение шкалы (°C -> K) на неё не влияет
+		let zero = self.convert_in(0.0, uncertainty_unit, unit, &RelativeContext::UNKNOWN)?;
+		Ok(Uncertain::new(value, self.convert_in(uncertainty, uncertainty_unit, unit, &RelativeContext::UNKNOWN)? - zero))
+	}
+
+	/// Перевод с погрешностью: производная берётся по краям интервала, так что смещения и нелинейные шкалы учитываются
+	pub fn convert_uncertain(&self, quantity: Uncertain, from: &ResolvedUnit, to: &ResolvedUnit, relative: &RelativeContext) -> Result<Uncertain, UnitError> {
+		let value = self.convert_in(quantity.value, from, to, relative)?;
+		if quantity.uncertainty == 0.0 {
+			return Ok(Uncertain::exact(value));
+		}
+
+		let upper = self.convert_in(quantity.value + quantity.uncertainty, from, to, relative)?;
+		let lower = self.convert_in(quantity.value - quantity.uncertainty, from, to, relative)?;
+		Ok(Uncertain::new(value, (upper - lower) / 2.0))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::shared::FormatOptions;
+
+	#[test]
+	fn uncertainty_takes_units_and_percent() {
+		let ctx = UnitContext::new();
+		let acceleration = ctx.unit("m/s2");
+		assert_eq!(ctx.uncertain(9.81, &acceleration, 0.02, Some(&acceleration)).unwrap(), Uncertain::new(9.81, 0.02));
+		assert_eq!(ctx.uncertain(200.0, &ctx.unit("W"), 2.0, Some(&ctx.unit("%"))).unwrap(), Uncertain::new(200.0, 4.0));
+		assert_eq!(ctx.uncertain(20.0, &ctx.unit("°C"), 1.0, Some(&ctx.unit("K"))).unwrap(), Uncertain::new(20.0, 1.0));
+	}
+
+	#[test]
+	fn uncertainty_propagates() {
+		let area = Uncertain::new(3.0, 0.3) * Uncertain::new(4.0, 0.4);
+		assert!((area.relative_uncertainty() - 0.1 * 2f64.sqrt()).abs() < 1e-12);
+		assert_eq!((Uncertain::new(1.0, 0.3) + Uncertain::new(2.0, 0.4)).uncertainty, 0.5);
+		assert!((Uncertain::new(2.0, 0.1).powi(2).uncertainty - 0.4).abs() < 1e-12);
+	}
+
+	#[test]
+	fn uncertain_values_convert_and_format() {
+		let ctx = UnitContext::new();
+		let km = ctx.convert_uncertain(Uncertain::new(1500.0, 20.0), &ctx.unit("m"), &ctx.unit("km"), &RelativeContext::UNKNOWN).unwrap();
+		assert!((km.value - 1.5).abs() < 1e-12 && (km.uncertainty - 0.02).abs() < 1e-12);
+
+		let one_digit = FormatOptions {
+			significant_digits: Some(1),
+			..FormatOptions::ASCII
+		};
+		assert_eq!(ctx.format_uncertain(Uncertain::new(1500.0, 20.0), &ctx.unit("m"), &one_digit), "(1.50 +/- 0.02)km");
+		assert_eq!(ctx.format_uncertain(Uncertain::new(9.81, 0.02), &ctx.unit("m/s2"), &FormatOptions::DEFAULT), "(9.810 ± 0.020)m/s²");
+	}
+
+	#[test]
+	fn overlapping_values_compare_equal() {
+		assert_eq!(Uncertain::new(10.0, 0.1).compare(&Uncertain::new(10.2, 0.1), 2.0), Ordering::Equal);
+		assert_eq!(Uncertain::new(10.0, 0.1).compare(&Uncertain::new(11.0, 0.1), 2.0), Ordering::Less);
+	}
+}
@@ -1,940 +1,1268 @@
 use super::UnitKind;
-use super::prefixes::PREFIXES;
-use super::{CalcMode, UnitProps};
-use super::{PrefixGroup, UnitArena, UnitDef, UnitTree};
+use super::prefixes::{PREFIX_NAMES, PREFIXES};
+use super::{CUSTOM_BASE_COUNT, Dimension, PrefixGroup, ResolvedUnit, UnitAlias, UnitBase, UnitDeclaration, UnitDef, UnitId, UnitNames, UnitParser, UnitPrefix, UnitSystem, UnitTree, UserUnit};
+use super::{CalcMode, CalendarDate, CalendarUnit, FormatOptions, Ratio, RelativeContext, RelativeTo, UnitProps};
+use crate::shared::{UnitError, closest};
 
+use std::borrow::Cow;
 use std::collections::BTreeMap;
+use std::sync::LazyLock;
 
 pub static UNITS: &[UnitDef] = units_array![
 				[]
 				UnitDef::new(
 								"Hz",
 								UnitKind::Frequency,
-								None,
-								PrefixGroup::SI,
 								PrefixGroup::SI,
 								UnitProps::DEFAULT
-				),
+				)
+				.named("hertz", "hertz", &[UnitAlias::ru(&["герц", "герца", "герц"])]),
+				// База массы — kg (как у lb = 0.45359237), поэтому у грамма множитель 0.001, а не 1
 				UnitDef::new(
 								"g",
 								UnitKind::Mass,
-								None,
 								PrefixGroup::SI,
-								PrefixGroup::SI,
-								UnitProps::DEFAULT,
-				),
+								UnitProps { scale: 0.001, exact: Some(Ratio::new(1, 1000)), ..UnitProps::DEFAULT },
+				)
+				.named("gram", "grams", &[UnitAlias::en(&["gramme", "grammes"]), UnitAlias::ru(&["грамм", "грамма", "граммов"])]),
 				UnitDef::new(
 								"lb",
 								UnitKind::Mass,
-								None,
-								PrefixGroup::SI,
 								PrefixGroup::SI,
-								UnitProps { scale: 0.45359237, ..UnitProps::DEFAULT },
-				),
+								UnitProps { scale: 0.45359237, exact: Some(Ratio::new(45359237, 100000000)), ..UnitProps::DEFAULT },
+				)
+				.in_system(UnitSystem::Customary)
+				.named("pound", "pounds", &[UnitAlias::ru(&["фунт", "фунта", "фунтов"])]),
 				UnitDef::new(
 								"m",
 								UnitKind::Length,
-								None,
-								PrefixGroup::SI,
 								PrefixGroup::SI,
 								UnitProps::DEFAULT,
-				),
+				)
+				.named("meter", "meters", &[UnitAlias::en(&["metre", "metres"]), UnitAlias::ru(&["метр", "метра", "метров"])]),
 				UnitDef::new(
 								"Å",
 								UnitKind::Length,
-								None,
-								PrefixGroup::SI,
-								PrefixGroup::SI,
-								UnitProps { scale: 1e-10, ..UnitProps::DEFAULT },
-				),
-				UnitDef::new(
-								"m2",
-								UnitKind::Area,
-								None,
-								PrefixGroup::SI,
 								PrefixGroup::SI,
-								UnitProps::DEFAULT,
-				),
-				UnitDef::new(
-								"m3",
-								UnitKind::Volume,
-								None,
-								PrefixGroup::SI,
-								PrefixGroup::SI,
-								UnitProps::DEFAULT,
-				),
+								UnitProps { scale: 1e-10, exact: Some(Ratio::new(1, 10000000000)), ..UnitProps::DEFAULT },
+				)
+				.in_system(UnitSystem::Metric)
+				.named("angstrom", "angstroms", &[UnitAlias::ru(&["ангстрем", "ангстрема", "ангстремов"])]),
 				UnitDef::new(
 								"L",
 								UnitKind::Volume,
-								None,
 								PrefixGroup::SI,
-								PrefixGroup::SI,
-								UnitProps { scale: 0.001, ..UnitProps::DEFAULT },
-				),
+								UnitProps { scale: 0.001, exact: Some(Ratio::new(1, 1000)), ..UnitProps::DEFAULT },
+				)
+				.in_system(UnitSystem::Metric)
+				.named("liter", "liters", &[UnitAlias::en(&["litre", "litres"]), UnitAlias::ru(&["литр", "литра", "литров"])]),
 				UnitDef::new(
 								"s",
 								UnitKind::Time,
-								None,
-								PrefixGroup::SI,
 								PrefixGroup::SI,
 								UnitProps::DEFAULT,
-				),
+				)
+				.named("second", "seconds", &[UnitAlias::ru(&["секунда", "секунды", "секунд", "секунду"])]),
 				UnitDef::new(
 								"sec",
 								UnitKind::Time,
-								None,
-								PrefixGroup::SI,
 								PrefixGroup::SI,
 								UnitProps::DEFAULT,
 				),
 				UnitDef::new(
 								"mol",
 								UnitKind::AmountOfSubstance,
-								None,
-								PrefixGroup::SI,
-								PrefixGroup::SI,
-								UnitProps::DEFAULT,
-				),
-				UnitDef::new(
-								"mol/m3",
-								UnitKind::MolarConcentration,
-								Some(("mol", "m3")),
-								PrefixGroup::SI,
-								PrefixGroup::SI,
-								UnitProps::DEFAULT,
-				),
-				UnitDef::new(
-								"mol/L",
-								UnitKind::MolarConcentration,
-								Some(("mol", "L")),
-								PrefixGroup::SI,
-								PrefixGroup::SI,
-								UnitProps { scale: 0.001, ..UnitProps::DEFAULT },
-				),
-				UnitDef::new(
-								"g/mol",
-								UnitKind::MolarConcentration,
-								Some(("g", "mol")),
-								PrefixGroup::SI,
 								PrefixGroup::SI,
 								UnitProps::DEFAULT,
-				),
+				)
+				.named("mole", "moles", &[UnitAlias::ru(&["моль", "моля", "молей"])]),
 				UnitDef::new(
-								"m3/g",
-								UnitKind::SpecificVolume,
-								Some(("m3", "g")),
-								PrefixGroup::SI,
-								PrefixGroup::SI,
-								UnitProps::DEFAULT,
-				),
-				UnitDef::new(
-								"m3/mol",
-								UnitKind::MolarVolume,
-								Some(("m3", "mol")),
-								PrefixGroup::SI,
-								PrefixGroup::SI,
-								UnitProps::DEFAULT,
-				),
-				UnitDef::new(
-								"Da", // Атомная единица массы
+								"Da", // Атомная единица массы; 1.66e-27 кг (1.66e-24 — это граммы)
 								UnitKind::Mass,
-								None,
-								PrefixGroup::SI,
-								PrefixGroup::SI,
-								UnitProps { scale: 1.66053906660e-24, ..UnitProps::DEFAULT },
-				),
-				UnitDef::new(
-								"J/mol",
-								UnitKind::MolarEnergy,
-								Some(("J", "mol")),
-								PrefixGroup::SI,
-								PrefixGroup::SI,
-								UnitProps::DEFAULT,
-				),
-				UnitDef::new(
-								"J/mol⋅K",
-								UnitKind::MolarEntropy,
-								Some(("J", "mol⋅K")),
-								PrefixGroup::SI,
-								PrefixGroup::SI,
-								UnitProps::DEFAULT,
-				),
-				UnitDef::new(
-								"J/mol*K",
-								UnitKind::MolarEntropy,
-								Some(("J", "mol*K")),
-								PrefixGroup::SI,
-								PrefixGroup::SI,
-								UnitProps::DEFAULT,
-				),
-				UnitDef::new(
-								"g⋅m/s",
-								UnitKind::Momentum,
-								Some(("g⋅m", "s")),
-								PrefixGroup::SI,
-								PrefixGroup::SI,
-								UnitProps::DEFAULT,
-				),
-				UnitDef::new(
-								"g*m/s",
-								UnitKind::Momentum,
-								Some(("g*m", "s")),
-								PrefixGroup::SI,
-								PrefixGroup::SI,
-								UnitProps::DEFAULT,
-				),
-				//
-				//
-				UnitDef::new(
-								"g/m2",
-								UnitKind::AreaDensity,
-								Some(("g", "m2")),
-								PrefixGroup::SI,
-								PrefixGroup::SI,
-								UnitProps::DEFAULT
-				),
-				UnitDef::new(
-								"g/m3",
-								UnitKind::Density,
-								Some(("g", "m3")),
-								PrefixGroup::SI,
-								PrefixGroup::SI,
-								UnitProps::DEFAULT
-				),
-				//
-				UnitDef::new(
-								"m/s",
-								UnitKind::Velocity,
-								Some(("m", "s")),
-								PrefixGroup::SI,
-								PrefixGroup::SI,
-								UnitProps::DEFAULT
-				),
-				UnitDef::new(
-								"m/h",
-								UnitKind::Velocity,
-								None,
-								PrefixGroup::SI,
-								PrefixGroup::SI,
-								UnitProps::DEFAULT
-				),
-				UnitDef::new(
-								"m/s2",
-								UnitKind::Acceleration,
-								Some(("m", "s2")),
-								PrefixGroup::SI,
-								PrefixGroup::SI,
-								UnitProps::DEFAULT
-				),
-				UnitDef::new(
-								"m/s3",
-								UnitKind::Jerk,
-								Some(("m", "s3")),
-								PrefixGroup::SI,
-								PrefixGroup::SI,
-								UnitProps::DEFAULT
-				),
-				UnitDef::new(
-								"m/s4",
-								UnitKind::Snap,
-								Some(("m", "s4")),
-								PrefixGroup::SI,
-								PrefixGroup::SI,
-								UnitProps::DEFAULT
-				),
-				UnitDef::new(
-								"m/s5",
-								UnitKind::Crackle,
-								Some(("m", "s5")),
-								PrefixGroup::SI,
-								PrefixGroup::SI,
-								UnitProps::DEFAULT
-				),
-				UnitDef::new(
-								"m/s6",
-								UnitKind::Pop,
-								Some(("m", "s6")),
-								PrefixGroup::SI,
 								PrefixGroup::SI,
-								UnitProps::DEFAULT
-				),
+								UnitProps { scale: 1.66053906660e-27, ..UnitProps::DEFAULT },
+				)
+				.in_system(UnitSystem::Metric)
+				.named("dalton", "daltons", &[UnitAlias::ru(&["дальтон", "дальтона", "дальтонов"])]),
 				//
 				UnitDef::new(
 								"bit",
 								UnitKind::Information,
-								None,
-								PrefixGroup::SI,
-								PrefixGroup::SI,
+								PrefixGroup::Digital,
 								UnitProps::DEFAULT
-				),
+				)
+				.in_system(UnitSystem::Universal)
+				.named("bit", "bits", &[UnitAlias::ru(&["бит", "бита", "бит"])]),
 				UnitDef::new(
 								"Byte",
 								UnitKind::Information,
-								None,
-								PrefixGroup::SI,
-								PrefixGroup::SI,
+								PrefixGroup::Digital,
 								UnitProps { scale: 8.0, ..UnitProps::DEFAULT }
-				),
-				UnitDef::new(
-								"bit/s",
-								UnitKind::BitRate,
-								Some(("bit", "s")),
-								PrefixGroup::SI,
-								PrefixGroup::SI,
-								UnitProps::DEFAULT
-				),
-				UnitDef::new(
-								"Byte/s",
-								UnitKind::BitRate,
-								Some(("B", "s")),
-								PrefixGroup::SI,
-								PrefixGroup::SI,
-								UnitProps { scale: 8.0, ..UnitProps::DEFAULT }
-				),
+				)
+				.in_system(UnitSystem::Universal)
+				.named("byte", "bytes", &[UnitAlias::ru(&["байт", "байта", "байт"])]),
 				//
 				UnitDef::new(
 								"Bel",
 								UnitKind::LogarithmicRatio,
-								None,
-								PrefixGroup::SI,
 								PrefixGroup::SI,
 								UnitProps::DEFAULT
-				),
+				)
+				.in_system(UnitSystem::Metric),
 				//
 				UnitDef::new(
 								"pH",
 								UnitKind::Acidity,
-								None,
-								PrefixGroup::SI,
 								PrefixGroup::SI,
 								UnitProps::DEFAULT
-				),
+				)
+				.in_system(UnitSystem::Universal),
 				//
+				// Тонна — 1000 кг (1e6 было бы в граммах)
 				UnitDef::new(
 								"t",
 								UnitKind::Mass,
-								None,
-								PrefixGroup::None,
 								PrefixGroup::None,
 								UnitProps {
-												scale: 1e6,
+												scale: 1e3,
 												..UnitProps::DEFAULT
 								},
-				),
+				)
+				.in_system(UnitSystem::Metric)
+				.named("tonne", "tonnes", &[UnitAlias::ru(&["тонна", "тонны", "тонн", "тонну"])]),
 				//
 				UnitDef::new(
 								"ft",
 								UnitKind::Length,
-								None,
-								PrefixGroup::None,
 								PrefixGroup::None,
 								UnitProps {
 												scale: 0.3048,
+												exact: Some(Ratio::new(3048, 10000)),
 												..UnitProps::DEFAULT
 								},
-				),
+				)
+				.in_system(UnitSystem::Customary)
+				.named("foot", "feet", &[UnitAlias::ru(&["фут", "фута", "футов"])]),
 				UnitDef::new(
 								"mi",
 								UnitKind::Length,
-								None,
-								PrefixGroup::None,
 								PrefixGroup::None,
 								UnitProps {
 												scale: 1609.344,
+												exact: Some(Ratio::new(1609344, 1000)),
 												..UnitProps::DEFAULT
 								},
-				),
+				)
+				.in_system(UnitSystem::Customary)
+				.named("mile", "miles", &[UnitAlias::ru(&["миля", "мили", "миль", "милю"])]),
 				UnitDef::new(
 								"in",
 								UnitKind::Length,
-								None,
-								PrefixGroup::None,
 								PrefixGroup::None,
 								UnitProps {
 												scale: 0.0254,
+												exact: Some(Ratio::new(254, 10000)),
 												..UnitProps::DEFAULT
 								},
-				),
+				)
+				.in_system(UnitSystem::Customary)
+				.named("inch", "inches", &[UnitAlias::ru(&["дюйм", "дюйма", "дюймов"])]),
 				UnitDef::new(
 								"em",
 								UnitKind::Length,
-								None,
-								PrefixGroup::None,
 								PrefixGroup::None,
-								UnitProps::DEFAULT,
-				),
+								UnitProps {
+												mode: CalcMode::Relative(RelativeTo::FontSize),
+												..UnitProps::DEFAULT
+								},
+				)
+				.in_system(UnitSystem::Universal),
 				UnitDef::new(
 								"rem",
 								UnitKind::Length,
-								None,
-								PrefixGroup::None,
 								PrefixGroup::None,
-								UnitProps::DEFAULT,
-				),
+								UnitProps {
+												mode: CalcMode::Relative(RelativeTo::RootFontSize),
+												..UnitProps::DEFAULT
+								},
+				)
+				.in_system(UnitSystem::Universal),
 				UnitDef::new(
 								"pt",
 								UnitKind::Length,
-								None,
-								PrefixGroup::None,
 								PrefixGroup::None,
 								UnitProps {
-												scale: 0.000352778,
+												scale: 0.0254 / 72.0,
+												exact: Some(Ratio::new(254, 720000)),
 												..UnitProps::DEFAULT
 								},
-				),
+				)
+				.in_system(UnitSystem::Universal)
+				.named("point", "points", &[UnitAlias::ru(&["пункт", "пункта", "пунктов"])]),
 				UnitDef::new(
 								"pc",
 								UnitKind::Length,
-								None,
-								PrefixGroup::None,
 								PrefixGroup::None,
 								UnitProps {
-												scale: 0.004233333,
+												scale: 0.0254 / 6.0,
+												exact: Some(Ratio::new(254, 60000)),
 												..UnitProps::DEFAULT
 								},
-				),
+				)
+				.in_system(UnitSystem::Universal),
 				UnitDef::new(
 								"px",
 								UnitKind::Length,
-								None,
-								PrefixGroup::None,
 								PrefixGroup::None,
-								UnitProps::DEFAULT,
-				),
+								UnitProps {
+												mode: CalcMode::Relative(RelativeTo::Pixel),
+												..UnitProps::DEFAULT
+								},
+				)
+				.in_system(UnitSystem::Universal)
+				.named("pixel", "pixels", &[UnitAlias::ru(&["пиксель", "пикселя", "пикселей"])]),
 				//
 				UnitDef::new(
 								"min",
 								UnitKind::Time,
-								None,
-								PrefixGroup::None,
 								PrefixGroup::None,
 								UnitProps {
 												scale: 60.0,
 												..UnitProps::DEFAULT
 								},
-				),
+				)
+				.in_system(UnitSystem::Metric)
+				.named("minute", "minutes", &[UnitAlias::ru(&["минута", "минуты", "минут", "минуту"])]),
 				UnitDef::new(
 								"hour",
 								UnitKind::Time,
-								None,
 								PrefixGroup::None,
+								UnitProps {
+												scale: 3600.0,
+												..UnitProps::DEFAULT
+								},
+				)
+				.in_system(UnitSystem::Metric),
+				UnitDef::new(
+								"h",
+								UnitKind::Time,
 								PrefixGroup::None,
 								UnitProps {
 												scale: 3600.0,
 												..UnitProps::DEFAULT
 								},
-				),
+				)
+				.in_system(UnitSystem::Metric)
+				.named("hour", "hours", &[UnitAlias::ru(&["час", "часа", "часов"])]),
 				UnitDef::new(
 								"day",
 								UnitKind::Time,
-								None,
-								PrefixGroup::None,
 								PrefixGroup::None,
 								UnitProps {
 												scale: 86400.0,
 												..UnitProps::DEFAULT
 								},
-				),
+				)
+				.in_system(UnitSystem::Metric)
+				.named("day", "days", &[UnitAlias::ru(&["день", "дня", "дней"])]),
 				UnitDef::new(
 								"week",
 								UnitKind::Time,
-								None,
-								PrefixGroup::None,
 								PrefixGroup::None,
 								UnitProps {
 												scale: 604800.0,
 												..UnitProps::DEFAULT
 								},
-				),
+				)
+				.in_system(UnitSystem::Universal)
+				.named("week", "weeks", &[UnitAlias::ru(&["неделя", "недели", "недель", "неделю"])]),
 				UnitDef::new(
 								"month",
 								UnitKind::Time,
-								None,
-								PrefixGroup::None,
 								PrefixGroup::None,
 								UnitProps {
 												scale: 2629746.0,
 												..UnitProps::DEFAULT
 								},
-				),
+				)
+				.in_system(UnitSystem::Universal)
+				.named("month", "months", &[UnitAlias::ru(&["месяц", "месяца", "месяцев"])]),
 				UnitDef::new(
 								"year",
 								UnitKind::Time,
-								None,
-								PrefixGroup::None,
 								PrefixGroup::None,
 								UnitProps {
 												scale: 31556952.0,
 												..UnitProps::DEFAULT
 								},
-				),
-				//
+				)
+				.in_system(UnitSystem::Universal)
+				.named("year", "years", &[UnitAlias::ru(&["год", "года", "лет"])]),
+				// Календарные: средняя длина в scale только для справки, convert её не использует
 				UnitDef::new(
-								"ft/s",
-								UnitKind::Velocity,
-								Some(("m", "s")),
+								"calday",
+								UnitKind::Time,
 								PrefixGroup::None,
+								UnitProps {
+												scale: 86400.0,
+												mode: CalcMode::Calendar(CalendarUnit::Day),
+												..UnitProps::DEFAULT
+								},
+				)
+				.in_system(UnitSystem::Universal),
+				UnitDef::new(
+								"calweek",
+								UnitKind::Time,
 								PrefixGroup::None,
-								UnitProps { scale: 0.3048, ..UnitProps::DEFAULT }
-				),
+								UnitProps {
+												scale: 604800.0,
+												mode: CalcMode::Calendar(CalendarUnit::Week),
+												..UnitProps::DEFAULT
+								},
+				)
+				.in_system(UnitSystem::Universal),
 				UnitDef::new(
-								"mi/h",
-								UnitKind::Velocity,
-								Some(("m", "s")),
+								"calmonth",
+								UnitKind::Time,
 								PrefixGroup::None,
+								UnitProps {
+												scale: 2629746.0,
+												mode: CalcMode::Calendar(CalendarUnit::Month),
+												..UnitProps::DEFAULT
+								},
+				)
+				.in_system(UnitSystem::Universal),
+				UnitDef::new(
+								"calyear",
+								UnitKind::Time,
 								PrefixGroup::None,
-								UnitProps { scale: 0.44704, ..UnitProps::DEFAULT }
-				),
+								UnitProps {
+												scale: 31556952.0,
+												mode: CalcMode::Calendar(CalendarUnit::Year),
+												..UnitProps::DEFAULT
+								},
+				)
+				.in_system(UnitSystem::Universal),
+				//
 				UnitDef::new(
 								"kn",
 								UnitKind::Velocity,
-								Some(("m", "s")),
 								PrefixGroup::None,
-								PrefixGroup::None,
-								UnitProps { scale: 0.514444, ..UnitProps::DEFAULT }
-				),
+								UnitProps { scale: 1852.0 / 3600.0, exact: Some(Ratio::new(1852, 3600)), ..UnitProps::DEFAULT }
+				)
+				.in_system(UnitSystem::Customary)
+				.named("knot", "knots", &[UnitAlias::ru(&["узел", "узла", "узлов"])]),
 				//
 				UnitDef::new(
 								"K",
 								UnitKind::Temperature,
-								None,
-								PrefixGroup::None,
 								PrefixGroup::None,
 								UnitProps::DEFAULT,
-				),
-				@multi ["deg", "\u{00B0}"] "C", UnitKind::Temperature, (PrefixGroup::None, PrefixGroup::None), UnitProps {
+				)
+				.named("kelvin", "kelvins", &[UnitAlias::ru(&["кельвин", "кельвина", "кельвинов"])]),
+				@multi ["deg", "\u{00B0}"] "C", UnitKind::Temperature, PrefixGroup::None, UnitProps {
 								offset: 273.15,
 								..UnitProps::DEFAULT
 				},
-				@multi ["deg", "\u{00B0}"] "F", UnitKind::Temperature, (PrefixGroup::None, PrefixGroup::None), UnitProps {
+				@multi ["deg", "\u{00B0}"] "F", UnitKind::Temperature, PrefixGroup::None, UnitProps {
 								scale: 5.0 / 9.0,
 								offset: 255.3722222222222,
 								..UnitProps::DEFAULT
-				},
-				@multi ["deg", "\u{00B0}"] "De", UnitKind::Temperature, (PrefixGroup::None, PrefixGroup::None), UnitProps {
+				} => UnitSystem::Customary,
+				@multi ["deg", "\u{00B0}"] "De", UnitKind::Temperature, PrefixGroup::None, UnitProps {
 								scale: -2.0 / 3.0,
 								offset: 373.15,
 								..UnitProps::DEFAULT
-				},
-				@multi ["deg", "\u{00B0}"] "Ra", UnitKind::Temperature, (PrefixGroup::None, PrefixGroup::None), UnitProps {
+				} => UnitSystem::Historic,
+				@multi ["deg", "\u{00B0}"] "Ra", UnitKind::Temperature, PrefixGroup::None, UnitProps {
 								scale: 5.0 / 9.0,
 								..UnitProps::DEFAULT
-				},
-				@multi ["deg", "\u{00B0}"] "N", UnitKind::Temperature, (PrefixGroup::None, PrefixGroup::None), UnitProps {
+				} => UnitSystem::Customary,
+				@multi ["deg", "\u{00B0}"] "N", UnitKind::Temperature, PrefixGroup::None, UnitProps {
 								scale: 100.0 / 33.0,
 								offset: 273.15,
 								..UnitProps::DEFAULT
-				},
-				@multi ["deg", "\u{00B0}"] "D", UnitKind::Temperature, (PrefixGroup::None, PrefixGroup::None), UnitProps {
+				} => UnitSystem::Historic,
+				@multi ["deg", "\u{00B0}"] "D", UnitKind::Temperature, PrefixGroup::None, UnitProps {
 								scale: -2.0 / 3.0,
 								offset: 373.15,
 								..UnitProps::DEFAULT
-				},
-				@multi ["deg", "\u{00B0}"] "Re", UnitKind::Temperature, (PrefixGroup::None, PrefixGroup::None), UnitProps {
+				} => UnitSystem::Historic,
+				@multi ["deg", "\u{00B0}"] "Re", UnitKind::Temperature, PrefixGroup::None, UnitProps {
 								scale: 1.25,
 								offset: 273.15,
 								..UnitProps::DEFAULT
-				},
-				@multi ["deg", "\u{00B0}"] "Ro", UnitKind::Temperature, (PrefixGroup::None, PrefixGroup::None), UnitProps {
+				} => UnitSystem::Historic,
+				@multi ["deg", "\u{00B0}"] "Ro", UnitKind::Temperature, PrefixGroup::None, UnitProps {
 								scale: 40.0 / 21.0,
 								offset: 258.864286,
 								..UnitProps::DEFAULT
-				},
-				@multi ["deg", "\u{00B0}"] "L", UnitKind::Temperature, (PrefixGroup::None, PrefixGroup::None), UnitProps {
+				} => UnitSystem::Historic,
+				@multi ["deg", "\u{00B0}"] "L", UnitKind::Temperature, PrefixGroup::None, UnitProps {
 								offset: 20.15,
 								..UnitProps::DEFAULT
-				},
-				@multi ["deg", "\u{00B0}"] "W", UnitKind::Temperature, (PrefixGroup::None, PrefixGroup::None), UnitProps {
+				} => UnitSystem::Historic,
+				@multi ["deg", "\u{00B0}"] "W", UnitKind::Temperature, PrefixGroup::None, UnitProps {
 								scale: 24.857191,
 								offset: 542.15,
 								..UnitProps::DEFAULT
-				},
-				@multi ["deg", "\u{00B0}"] "Da", UnitKind::Temperature, (PrefixGroup::None, PrefixGroup::None), UnitProps {
+				} => UnitSystem::Historic,
+				@multi ["deg", "\u{00B0}"] "Da", UnitKind::Temperature, PrefixGroup::None, UnitProps {
 								scale: 373.15,
 								offset: 273.15,
 								mode: CalcMode::Exponential,
 								..UnitProps::DEFAULT
-				},
+				} => UnitSystem::Historic,
 				//
 				UnitDef::new(
 								"V",
 								UnitKind::ElectricVoltage,
-								None,
-								PrefixGroup::SI,
 								PrefixGroup::SI,
 								UnitProps::DEFAULT
-				),
+				)
+				.named("volt", "volts", &[UnitAlias::ru(&["вольт", "вольта", "вольт"])]),
 				UnitDef::new(
 								"A",
 								UnitKind::ElectricCurrent,
-								None,
-								PrefixGroup::SI,
 								PrefixGroup::SI,
 								UnitProps::DEFAULT
-				),
+				)
+				.named("ampere", "amperes", &[UnitAlias::en(&["amp", "amps"]), UnitAlias::ru(&["ампер", "ампера", "ампер"])]),
 				UnitDef::new(
 								"W",
 								UnitKind::Power,
-								None,
-								PrefixGroup::SI,
-								PrefixGroup::SI,
-								UnitProps::DEFAULT
-				),
-				UnitDef::new(
-								"J/s",
-								UnitKind::Power,
-								Some(("J", "s")),
-								PrefixGroup::SI,
 								PrefixGroup::SI,
 								UnitProps::DEFAULT
-				),
+				)
+				.named("watt", "watts", &[UnitAlias::ru(&["ватт", "ватта", "ватт"])]),
 				UnitDef::new(
 								"Ω",
 								UnitKind::ElectricResistance,
-								None,
-								PrefixGroup::SI,
 								PrefixGroup::SI,
 								UnitProps::DEFAULT
-				),
+				)
+				.named("ohm", "ohms", &[UnitAlias::ru(&["ом", "ома", "ом"])]),
 				UnitDef::new(
 								"ohm",
 								UnitKind::ElectricResistance,
-								None,
-								PrefixGroup::SI,
 								PrefixGroup::SI,
 								UnitProps::DEFAULT
 				),
 				UnitDef::new(
 								"S",
 								UnitKind::ElectricConductance,
-								None,
-								PrefixGroup::SI,
 								PrefixGroup::SI,
 								UnitProps::DEFAULT
-				),
+				)
+				.named("siemens", "siemens", &[UnitAlias::ru(&["сименс", "сименса", "сименс"])]),
 				UnitDef::new(
 								"siemens",
 								UnitKind::ElectricConductance,
-								None,
-								PrefixGroup::SI,
 								PrefixGroup::SI,
 								UnitProps::DEFAULT
 				),
 				UnitDef::new(
 								"C",
 								UnitKind::ElectricCharge,
-								None,
-								PrefixGroup::SI,
 								PrefixGroup::SI,
 								UnitProps::DEFAULT
-				),
+				)
+				.named("coulomb", "coulombs", &[UnitAlias::ru(&["кулон", "кулона", "кулонов"])]),
 				UnitDef::new(
 								"F",
 								UnitKind::ElectricCapacitance,
-								None,
-								PrefixGroup::SI,
 								PrefixGroup::SI,
 								UnitProps::DEFAULT
-				),
-				//
+				)
+				.named("farad", "farads", &[UnitAlias::ru(&["фарад", "фарада", "фарад"])]),
 				UnitDef::new(
-								"J",
-								UnitKind::Energy,
-								None,
-								PrefixGroup::SI,
+								"Wb",
+								UnitKind::MagneticFlux,
 								PrefixGroup::SI,
 								UnitProps::DEFAULT
-				),
+				)
+				.named("weber", "webers", &[UnitAlias::ru(&["вебер", "вебера", "веберов"])]),
 				UnitDef::new(
-								"W*s",
-								UnitKind::Energy,
-								Some(("W", "s")),
-								PrefixGroup::SI,
+								"T",
+								UnitKind::MagneticFluxDensity,
 								PrefixGroup::SI,
 								UnitProps::DEFAULT
-				),
+				)
+				.named("tesla", "teslas", &[UnitAlias::ru(&["тесла", "теслы", "тесла"])]),
 				UnitDef::new(
-								"W⋅s",
-								UnitKind::Energy,
-								Some(("W", "s")),
-								PrefixGroup::SI,
+								"H",
+								UnitKind::Inductance,
 								PrefixGroup::SI,
 								UnitProps::DEFAULT
-				),
+				)
+				.named("henry", "henries", &[UnitAlias::ru(&["генри", "генри", "генри"])]),
+				//
 				UnitDef::new(
-								"W*s",
+								"J",
 								UnitKind::Energy,
-								Some(("W", "s")),
-								PrefixGroup::SI,
 								PrefixGroup::SI,
 								UnitProps::DEFAULT
-				),
+				)
+				.named("joule", "joules", &[UnitAlias::ru(&["джоуль", "джоуля", "джоулей"])]),
+				// 1 Вт⋅ч = 3600 Дж. `W*s` отдельной записью не нужен: его собирает разбор выражений
 				UnitDef::new(
 								"Wh",
 								UnitKind::Energy,
-								None,
 								PrefixGroup::SI,
-								PrefixGroup::SI,
-								UnitProps::DEFAULT
-				),
+								UnitProps { scale: 3600.0, ..UnitProps::DEFAULT }
+				)
+				.in_system(UnitSystem::Metric),
 				UnitDef::new(
 								"eV",
 								UnitKind::Energy,
-								None,
 								PrefixGroup::SI,
-								PrefixGroup::SI,
-								UnitProps { scale: 1.602176634e-19, ..UnitProps::DEFAULT }
-				),
+								UnitProps { scale: 1.602176634e-19, exact: Some(Ratio::new(1602176634, 10000000000000000000000000000)), ..UnitProps::DEFAULT }
+				)
+				.in_system(UnitSystem::Metric)
+				.named("electronvolt", "electronvolts", &[UnitAlias::ru(&["электронвольт", "электронвольта", "электронвольт"])]),
 				UnitDef::new(
 								"erg",
 								UnitKind::Energy,
-								None,
 								PrefixGroup::SI,
-								PrefixGroup::SI,
-								UnitProps { scale: 1e-7, ..UnitProps::DEFAULT }
-				),
+								UnitProps { scale: 1e-7, exact: Some(Ratio::new(1, 10000000)), ..UnitProps::DEFAULT }
+				)
+				.in_system(UnitSystem::CGS),
 				//
 				UnitDef::new(
 								"Pa",
 								UnitKind::Pressure,
-								None,
-								PrefixGroup::SI,
 								PrefixGroup::SI,
 								UnitProps::DEFAULT
-				),
+				)
+				.named("pascal", "pascals", &[UnitAlias::ru(&["паскаль", "паскаля", "паскалей"])]),
 				UnitDef::new(
 								"N",
 								UnitKind::Force,
-								None,
-								PrefixGroup::SI,
 								PrefixGroup::SI,
 								UnitProps::DEFAULT
-				),
-				//
+				)
+				.named("newton", "newtons", &[UnitAlias::ru(&["ньютон", "ньютона", "ньютонов"])]),
 				UnitDef::new(
-								"lm",
-								UnitKind::LuminousIntensity,
-								None,
+								"St",
+								UnitKind::KinematicViscosity,
 								PrefixGroup::SI,
+								UnitProps { scale: 1e-4, exact: Some(Ratio::new(1, 10000)), ..UnitProps::DEFAULT }
+				)
+				.in_system(UnitSystem::CGS)
+				.named("stokes", "stokes", &[UnitAlias::ru(&["стокс", "стокса", "стоксов"])]),
+				// Кандела — сила света (база), люмен — световой поток, люкс — освещённость
+				UnitDef::new(
+								"lm",
+								UnitKind::LuminousFlux,
 								PrefixGroup::SI,
 								UnitProps::DEFAULT
-				),
+				)
+				.named("lumen", "lumens", &[UnitAlias::ru(&["люмен", "люмена", "люменов"])]),
 				UnitDef::new(
 								"lx",
+								UnitKind::Illuminance,
+								PrefixGroup::SI,
+								UnitProps::DEFAULT
+				)
+				.named("lux", "lux", &[UnitAlias::ru(&["люкс", "люкса", "люксов"])]),
+				UnitDef::new(
+								"cd",
 								UnitKind::LuminousIntensity,
-								None,
 								PrefixGroup::SI,
+								UnitProps::DEFAULT
+				)
+				.named("candela", "candelas", &[UnitAlias::ru(&["кандела", "канделы", "кандел"])]),
+				//
+				UnitDef::new(
+								"Bq",
+								UnitKind::Activity,
 								PrefixGroup::SI,
 								UnitProps::DEFAULT
-				),
+				)
+				.named("becquerel", "becquerels", &[UnitAlias::ru(&["беккерель", "беккереля", "беккерелей"])]),
 				UnitDef::new(
-								"cd",
-								UnitKind::LuminousFlux,
-								None,
+								"Ci",
+								UnitKind::Activity,
+								PrefixGroup::None,
+								UnitProps { scale: 3.7e10, ..UnitProps::DEFAULT }
+				)
+				.in_system(UnitSystem::Historic)
+				.named("curie", "curies", &[UnitAlias::ru(&["кюри", "кюри", "кюри"])]),
+				UnitDef::new(
+								"Gy",
+								UnitKind::AbsorbedDose,
 								PrefixGroup::SI,
+								UnitProps::DEFAULT
+				)
+				.named("gray", "grays", &[UnitAlias::ru(&["грей", "грея", "греев"])]),
+				UnitDef::new(
+								"Sv",
+								UnitKind::EquivalentDose,
 								PrefixGroup::SI,
 								UnitProps::DEFAULT
-				),
+				)
+				.named("sievert", "sieverts", &[UnitAlias::ru(&["зиверт", "зиверта", "зивертов"])]),
+				UnitDef::new(
+								"kat",
+								UnitKind::CatalyticActivity,
+								PrefixGroup::SI,
+								UnitProps::DEFAULT
+				)
+				.named("katal", "katals", &[UnitAlias::ru(&["катал", "катала", "каталов"])]),
 				//
 				UnitDef::new(
 								"%",
 								UnitKind::Percent,
-								None,
 								PrefixGroup::None,
+								UnitProps {
+												scale: 0.01,
+												exact: Some(Ratio::new(1, 100)),
+												..UnitProps::DEFAULT
+								},
+				)
+				.in_system(UnitSystem::Universal)
+				.named("percent", "percent", &[UnitAlias::ru(&["процент", "процента", "процентов"])]),
+				UnitDef::new(
+								"pp",
+								UnitKind::PercentagePoint,
 								PrefixGroup::None,
 								UnitProps {
 												scale: 0.01,
+												exact: Some(Ratio::new(1, 100)),
 												..UnitProps::DEFAULT
 								},
-				),
+				)
+				.in_system(UnitSystem::Universal),
 				UnitDef::new(
 								"‰",
 								UnitKind::Permille,
-								None,
-								PrefixGroup::None,
 								PrefixGroup::None,
 								UnitProps {
 												scale: 0.001,
+												exact: Some(Ratio::new(1, 1000)),
 												..UnitProps::DEFAULT
 								},
-				),
+				)
+				.in_system(UnitSystem::Universal),
 				UnitDef::new(
 								"‱",
 								UnitKind::PerTenThousand,
-								None,
-								PrefixGroup::None,
 								PrefixGroup::None,
 								UnitProps {
 												scale: 0.0001,
+												exact: Some(Ratio::new(1, 10000)),
 												..UnitProps::DEFAULT
 								},
-				),
+				)
+				.in_system(UnitSystem::Universal),
 				UnitDef::new(
 								"ppm",
 								UnitKind::PartPerMillion,
-								None,
-								PrefixGroup::None,
 								PrefixGroup::None,
 								UnitProps {
 												scale: 0.000001,
+												exact: Some(Ratio::new(1, 1000000)),
 												..UnitProps::DEFAULT
 								},
-				),
+				)
+				.in_system(UnitSystem::Universal),
 				UnitDef::new(
 								"ppb",
 								UnitKind::PartPerBillion,
-								None,
-								PrefixGroup::None,
 								PrefixGroup::None,
 								UnitProps {
 												scale: 0.000000001,
+												exact: Some(Ratio::new(1, 1000000000)),
 												..UnitProps::DEFAULT
 								},
-				),
+				)
+				.in_system(UnitSystem::Universal),
 				UnitDef::new(
 								"ppt",
 								UnitKind::PartPerTrillion,
-								None,
-								PrefixGroup::None,
 								PrefixGroup::None,
 								UnitProps {
 												scale: 0.000000000001,
+												exact: Some(Ratio::new(1, 1000000000000)),
 												..UnitProps::DEFAULT
 								},
-				),
+				)
+				.in_system(UnitSystem::Universal),
 				UnitDef::new(
 								"fr",
 								UnitKind::Fraction,
-								None,
 								PrefixGroup::None,
+								UnitProps::DEFAULT,
+				)
+				.in_system(UnitSystem::Universal),
+				UnitDef::new(
+								"rad",
+								UnitKind::Angle,
+								PrefixGroup::SI,
+								UnitProps::DEFAULT,
+				)
+				.named("radian", "radians", &[UnitAlias::ru(&["радиан", "радиана", "радиан"])]),
+				UnitDef::new(
+								"sr",
+								UnitKind::SolidAngle,
 								PrefixGroup::None,
 								UnitProps::DEFAULT,
-				),
+				)
+				.named("steradian", "steradians", &[UnitAlias::ru(&["стерадиан", "стерадиана", "стерадиан"])]),
+				// Градус — угол, а `°C`, `°F` — отдельные символы температуры; самый длинный символ побеждает
 				UnitDef::new(
 								"deg",
-								UnitKind::Degree,
-								None,
-								PrefixGroup::None,
+								UnitKind::Angle,
 								PrefixGroup::None,
 								UnitProps {
-												scale: 0.017453292519943295,
+												scale: std::f64::consts::PI / 180.0,
 												..UnitProps::DEFAULT
 								},
-				),
+				)
+				.in_system(UnitSystem::Metric)
+				.named("degree", "degrees", &[UnitAlias::ru(&["градус", "градуса", "градусов"])]),
 				UnitDef::new(
 								"\u{00B0}",
-								UnitKind::Degree,
-								None,
+								UnitKind::Angle,
+								PrefixGroup::None,
+								UnitProps {
+												scale: std::f64::consts::PI / 180.0,
+												..UnitProps::DEFAULT
+								},
+				)
+				.in_system(UnitSystem::Metric),
+				UnitDef::new(
+								"arcmin",
+								UnitKind::Angle,
 								PrefixGroup::None,
+								UnitProps {
+												scale: std::f64::consts::PI / 10800.0,
+												..UnitProps::DEFAULT
+								},
+				)
+				.in_system(UnitSystem::Metric)
+				.named("arcminute", "arcminutes", &[]),
+				UnitDef::new(
+								"\u{2032}",
+								UnitKind::Angle,
 								PrefixGroup::None,
 								UnitProps {
-												scale: 0.017453292519943295,
+												scale: std::f64::consts::PI / 10800.0,
 												..UnitProps::DEFAULT
 								},
-				),
+				)
+				.in_system(UnitSystem::Metric),
 				UnitDef::new(
-								"rad",
-								UnitKind::Radian,
-								None,
+								"arcsec",
+								UnitKind::Angle,
 								PrefixGroup::None,
+								UnitProps {
+												scale: std::f64::consts::PI / 648000.0,
+												..UnitProps::DEFAULT
+								},
+				)
+				.in_system(UnitSystem::Metric)
+				.named("arcsecond", "arcseconds", &[]),
+				UnitDef::new(
+								"\u{2033}",
+								UnitKind::Angle,
 								PrefixGroup::None,
-								UnitProps::DEFAULT,
-				),
+								UnitProps {
+												scale: std::f64::consts::PI / 648000.0,
+												..UnitProps::DEFAULT
+								},
+				)
+				.in_system(UnitSystem::Metric),
+				UnitDef::new(
+								"turn",
+								UnitKind::Angle,
+								PrefixGroup::None,
+								UnitProps {
+												scale: std::f64::consts::TAU,
+												..UnitProps::DEFAULT
+								},
+				)
+				.in_system(UnitSystem::Universal)
+				.named("turn", "turns", &[UnitAlias::ru(&["оборот", "оборота", "оборотов"])]),
+				UnitDef::new(
+								"grad",
+								UnitKind::Angle,
+								PrefixGroup::None,
+								UnitProps {
+												scale: std::f64::consts::PI / 200.0,
+												..UnitProps::DEFAULT
+								},
+				)
+				.in_system(UnitSystem::Metric)
+				.named("gradian", "gradians", &[]),
+				UnitDef::new(
+								"rpm",
+								UnitKind::AngularVelocity,
+								PrefixGroup::None,
+								UnitProps { scale: std::f64::consts::TAU / 60.0, ..UnitProps::DEFAULT }
+				)
+				.in_system(UnitSystem::Universal),
 				UnitDef::new(
 								"D",
 								UnitKind::SpaceDimension,
-								None,
-								PrefixGroup::None,
 								PrefixGroup::None,
 								UnitProps::DEFAULT,
-				),
+				)
+				.in_system(UnitSystem::Universal),
 ];
 
+/// Встроенные юниты: дерево и таблица символов строятся один раз на процесс и разделяются между потоками
 #[derive(Debug)]
-pub struct UnitContext<'arena> {
-	pub arena: &'arena UnitArena,
+pub struct UnitRegistry {
 	pub tree: UnitTree,
-	// Теперь lookup использует байты, чтобы Scanner мог делать lookup.get(slice_u8)
-	pub lookup: BTreeMap<&'arena [u8], UnitKind>,
+	pub lookup: BTreeMap<&'static [u8], UnitId>,
+	// Полные названия и их формы; префиксы к ним тоже полные: `kilometers`, `километров`
+	pub names: UnitTree,
 }
 
-impl<'arena> UnitContext<'arena> {
-	pub fn new(arena: &'arena UnitArena) -> Self {
-		// Дерево обычно работает со строками внутри, оставляем как есть
-		let tree = build_unit_tree(UNITS, arena);
+pub static UNIT_REGISTRY: LazyLock<UnitRegistry> = LazyLock::new(|| UnitRegistry::new(UNITS));
 
-		let mut lookup = BTreeMap::new();
+impl UnitRegistry {
+	pub fn new(units: &'static [UnitDef]) -> Self {
+		let tree = build_unit_tree(units);
 
-		let mut grouped_p: BTreeMap<PrefixGroup, Vec<&str>> = BTreeMap::new();
-		for (p_sym, _, p_group) in PREFIXES {
-			grouped_p.entry(*p_group).or_default().push(*p_sym);
+		let mut lookup = BTreeMap::new();
+		for (index, unit) in units.iter().enumerate() {
+			let sym: &'static str = &unit.symbol;
+			lookup.insert(sym.as_bytes(), UnitId(index as u16));
 		}
 
-		for unit in UNITS {
-			// Аллоцируем строку и сразу берем её как &[u8] для мапы
-			let sym = arena.alloc_str(unit.symbol).as_bytes();
-			lookup.insert(sym, unit.dimension);
-
-			if let Some(prefixes) = grouped_p.get(&unit.numerator_group) {
-				for p_sym in prefixes {
-					let full = format!("{}{}", p_sym, unit.symbol);
-					let interned = arena.alloc_str(&full).as_bytes();
-					lookup.insert(interned, unit.dimension);
-				}
+		let mut names = UnitTree::default();
+		for (index, unit) in units.iter().enumerate() {
+			for name in unit.names.all() {
+				names.insert(name, UnitId(index as u16));
 			}
 		}
 
-		Self { arena, tree, lookup }
+		Self { tree, lookup, names }
 	}
 }
 
-pub fn build_unit_tree<'arena>(units: &[UnitDef], arena: &'arena UnitArena) -> UnitTree {
-	let mut tree = UnitTree::default();
-	// Оставляем как &str, так как это просто части для сборки имен
-	const SEPARATORS: &[&str] = &["/", "*", "⋅"];
+/// Контекст сканирования: общий реестр + слой пользовательских юнитов.
+/// Без пользовательских юнитов создание ничего не стоит; для файлов с разными импортами контекст клонируется
+#[derive(Debug, Clone)]
+pub struct UnitContext {
+	pub registry: &'static UnitRegistry,
+	// Юниты из `declare unit ...` и от хоста; их UnitId идут после UNITS
+	pub tree: UnitTree,
+	pub lookup: BTreeMap<Box<[u8]>, UnitId>,
+	pub user_units: Vec<UserUnit>,
+	// Имена пользовательских базовых величин, индекс = слот в Dimension
+	pub dimensions: Vec<String>,
+}
+
+impl Default for UnitContext {
+	fn default() -> Self {
+		Self::new()
+	}
+}
 
-	let mut gp: BTreeMap<PrefixGroup, Vec<&str>> = BTreeMap::new();
-	for (s, _, g) in PREFIXES {
-		gp.entry(*g).or_default().push(*s);
+impl UnitContext {
+	pub fn new() -> Self {
+		Self {
+			registry: &UNIT_REGISTRY,
+			tree: UnitTree::default(),
+			lookup: BTreeMap::new(),
+			user_units: Vec::new(),
+			dimensions: Vec::new(),
+		}
 	}
 
-	let default_prefix = vec![""];
+	pub fn definition(&self, id: UnitId) -> Option<&UnitDef> {
+		let index = id.0 as usize;
 
-	for unit in units {
-		if unit.symbol.is_empty() {
-			continue;
+		match UNITS.get(index) {
+			Some(def) => Some(def),
+			None => self.user_units.get(index - UNITS.len()).map(|unit| &unit.def),
 		}
+	}
 
-		tree.insert(unit.symbol);
+	pub fn dimension(&self, id: UnitId) -> Option<Dimension> {
+		let index = id.0 as usize;
 
-		if let Some((n_base, d_base)) = unit.parts {
-			let n_prefixes = gp.get(&unit.numerator_group).unwrap_or(&default_prefix);
-			let d_prefixes = gp.get(&unit.denominator_group).unwrap_or(&default_prefix);
+		match UNITS.get(index) {
+			Some(def) => Some(Dimension::of(def.dimension)),
+			None => self.user_units.get(index - UNITS.len()).map(|unit| unit.dimension),
+		}
+	}
 
-			for p_n in n_prefixes {
-				for p_d in d_prefixes {
-					if p_n.is_empty() && p_d.is_empty() {
-						continue;
-					}
+	/// Точный символ или полное название без префикса: `find(b"Pa")`, `find("минут".as_bytes())`
+	pub fn find(&self, symbol: &[u8]) -> Option<UnitId> {
+		let name = || self.registry.names.longest_match(symbol).filter(|(len, _)| *len == symbol.len()).map(|(_, id)| id);
 
-					for sep in SEPARATORS {
-						let full = format!("{}{}{}{}{}", p_n, n_base, sep, p_d, d_base);
-						let interned = arena.alloc_str(&full);
-						tree.insert(interned);
-					}
+		self.registry.lookup.get(symbol).or_else(|| self.lookup.get(symbol)).copied().or_else(name)
+	}
+
+	/// Самый длинный символ без префикса среди встроенных и пользовательских юнитов
+	pub fn longest_match(&self, input: &[u8]) -> Option<(usize, UnitId)> {
+		let builtin = self.registry.tree.longest_match(input);
+
+		match (builtin, self.tree.longest_match(input)) {
+			(Some(builtin), Some(user)) => Some(if user.0 > builtin.0 { user } else { builtin }),
+			(builtin, user) => builtin.or(user),
+		}
+	}
+
+	/// Юнит-выражение в начале `input`: длина совпадения и разобранный юнит
+	pub fn parse_unit(&self, input: &[u8]) -> Option<(usize, ResolvedUnit)> {
+		UnitParser::new(self, input).parse()
+	}
+
+	/// Юнит-выражение целиком: `resolve(b"kg⋅m²/s²")`
+	pub fn resolve(&self, symbol: &[u8]) -> Option<ResolvedUnit> {
+		self.parse_unit(symbol).filter(|(len, _)| *len == symbol.len()).map(|(_, unit)| unit)
+	}
+
+	/// Размер в байтах в принятой записи: `B`, `MB`, `GiB`. Такие суффиксы не опечатки, хотя юнит называется `Byte`
+	pub fn is_byte_size(&self, symbol: &str) -> bool {
+		symbol == "B" || PREFIXES.iter().any(|(prefix, _, group)| PrefixGroup::Digital.accepts(*group) && symbol.strip_prefix(prefix) == Some("B"))
+	}
+
+	/// Ближайший известный юнит к опечатке: `kgs` -> `kg`, `Kg` -> `kg`, `secnd` -> `second`
+	pub fn suggest_unit(&self, input: &str) -> Option<String> {
+		// Множественное число от символа — частая ошибка, её исправляем раньше поиска по расстоянию
+		if let Some(singular) = input.strip_suffix('s')
+			&& self.resolve(singular.as_bytes()).is_some()
+		{
+			return Some(singular.to_string());
+		}
+
+		let mut candidates = Vec::new();
+		let ids = (0..UNITS.len() + self.user_units.len()).map(|index| UnitId(index as u16));
+		for def in ids.filter_map(|id| self.definition(id)) {
+			candidates.push(def.symbol.to_string());
+			candidates.extend([def.names.singular, def.names.plural].into_iter().filter(|name| !name.is_empty()).map(str::to_string));
+			for (prefix, _, group) in PREFIXES {
+				if *group != PrefixGroup::None && def.prefix_group.accepts(*group) {
+					candidates.push(format!("{}{}", prefix, def.symbol));
 				}
 			}
-		} else if let Some(prefixes) = gp.get(&unit.numerator_group) {
-			for p_sym in prefixes {
-				if p_sym.is_empty() {
+		}
+
+		closest(input, candidates.iter().map(String::as_str)).map(str::to_string)
+	}
+
+	/// Самый длинный символ с допустимым префиксом; при равной длине побеждает символ без префикса (`min`, а не `m`+`in`)
+	pub fn match_symbol(&self, input: &[u8]) -> Option<(usize, Option<UnitPrefix>, UnitId)> {
+		let mut best = self.longest_match(input).map(|(len, id)| (len, None, id));
+
+		for (symbol, factor, group) in PREFIXES {
+			let Some(rest) = input.strip_prefix(symbol.as_bytes()) else {
+				continue;
+			};
+			let Some((len, id)) = self.longest_match(rest) else {
+				continue;
+			};
+			if self.definition(id).is_none_or(|def| !def.prefix_group.accepts(*group)) {
+				continue;
+			}
+
+			let total = symbol.len() + len;
+			if best.is_none_or(|(best_len, ..)| total > best_len) {
+				best = Some((total, Some(UnitPrefix { symbol, factor: *factor }), id));
+			}
+		}
+
+		// Полные названия: `meters`, `kilograms`, `минут`; полный префикс только с полным названием
+		if let Some((len, id)) = self.registry.names.longest_match(input)
+			&& best.is_none_or(|(best_len, ..)| len > best_len)
+		{
+			best = Some((len, None, id));
+		}
+		for (symbol, english, russian) in PREFIX_NAMES {
+			for name in [english, russian] {
+				let Some(rest) = input.strip_prefix(name.as_bytes()) else {
+					continue;
+				};
+				let Some((len, id)) = self.registry.names.longest_match(rest) else {
+					continue;
+				};
+				let Some((_, factor, group)) = PREFIXES.iter().find(|(s, ..)| s == symbol) else {
+					continue;
+				};
+				if self.definition(id).is_none_or(|def| !def.prefix_group.accepts(*group)) {
 					continue;
 				}
-				let full = format!("{}{}", p_sym, unit.symbol);
-				let interned = arena.alloc_str(&full);
-				tree.insert(interned);
+
+				let total = name.len() + len;
+				if best.is_none_or(|(best_len, ..)| total > best_len) {
+					best = Some((total, Some(UnitPrefix { symbol, factor: *factor }), id));
+				}
+			}
+		}
+
+		best
+	}
+
+	/// Регистрирует юнит: он сразу доступен сканеру, `resolve` и `convert`
+	pub fn declare(&mut self, declaration: UnitDeclaration) -> Result<UnitId, UnitError> {
+		let UnitDeclaration { symbol, base, scale, offset, prefix_group } = declaration;
+
+		// Цифры и операторы внутри символа сломали бы разбор степеней и выражений
+		if symbol.is_empty() || symbol.chars().any(|c| c.is_ascii_digit() || c.is_whitespace() || "*/^()[]{},;⋅·".contains(c)) {
+			return Err(UnitError::InvalidSymbol(symbol));
+		}
+		if self.resolve(symbol.as_bytes()).is_some() {
+			return Err(UnitError::DuplicateSymbol(symbol));
+		}
+
+		let id = u16::try_from(UNITS.len() + self.user_units.len()).map(UnitId).map_err(|_| UnitError::InvalidDeclaration("unit registry is full"))?;
+
+		// Множитель из исходника точен в той записи, в какой написан: `= 0.05 s` — это ровно 1/20
+		let literal = scale.to_string().parse::<Ratio>().ok();
+
+		let (kind, dimension, scale, offset, exact) = match base {
+			UnitBase::Kind(kind) => (kind, Dimension::of(kind), scale, offset, literal),
+			UnitBase::Dimension(name) => {
+				let index = self.dimensions.iter().position(|known| *known == name).unwrap_or(self.dimensions.len());
+				let dimension = Dimension::custom(index).ok_or(UnitError::TooManyDimensions(CUSTOM_BASE_COUNT))?;
+				if index == self.dimensions.len() {
+					self.dimensions.push(name);
+				}
+				(UnitKind::None, dimension, scale, offset, literal)
+			}
+			UnitBase::Unit(expression) => {
+				let Some(unit) = self.resolve(expression.as_bytes()) else {
+					return Err(UnitError::UnknownUnit(expression));
+				};
+				// Смещение базы учитывается только у одиночного юнита без префикса (`°C`)
+				let base_offset = match unit.factors.as_slice() {
+					[single] if single.exponent == 1 && single.prefix.is_none() => self.definition(single.unit).map_or(0.0, |def| def.props.offset),
+					_ => 0.0,
+				};
+				let exact = literal.zip(self.exact_scale(&unit)).and_then(|(literal, base)| literal.checked_mul(base));
+				(unit.kind, unit.dimension, scale * unit.scale, offset * unit.scale + base_offset, exact)
+			}
+		};
+
+		self.tree.insert(&symbol, id);
+		self.lookup.insert(symbol.as_bytes().into(), id);
+		self.user_units.push(UserUnit {
+			def: UnitDef {
+				symbol: Cow::Owned(symbol),
+				dimension: kind,
+				props: UnitProps { scale, offset, exact, ..UnitProps::DEFAULT },
+				prefix_group,
+				names: UnitNames::NONE,
+				// Свои юниты файла разрешены в любом профиле
+				system: UnitSystem::Universal,
+			},
+			dimension,
+		});
+
+		Ok(id)
+	}
+
+	/// Перевод значения между юнитами одной размерности: `convert(1.0, &km, &m)` = 1000
+	pub fn convert(&self, value: f64, from: &ResolvedUnit, to: &ResolvedUnit) -> Option<f64> {
+		self.convert_in(value, from, to, &RelativeContext::UNKNOWN).ok()
+	}
+
+	/// Перевод с учётом вёрстки; em/rem/px без известной базы дают `UnitError::Deferred`, значение остаётся как есть
+	pub fn convert_in(&self, value: f64, from: &ResolvedUnit, to: &ResolvedUnit, relative: &RelativeContext) -> Result<f64, UnitError> {
+		if !from.is_compatible(to) {
+			return match cycle_factor(from, to) {
+				Some(factor) => Ok(value * self.absolute_scale(from, relative)? * factor / self.absolute_scale(to, relative)?),
+				None => Err(UnitError::IncompatibleUnits(self.format_unit(from, &FormatOptions::DEFAULT), self.format_unit(to, &FormatOptions::DEFAULT))),
+			};
+		}
+		// Те же юниты базы не требуют: `2em -> em`
+		if from.factors == to.factors {
+			return Ok(value);
+		}
+		// Календарные переводятся только внутри своей группы: calyear -> calmonth, calweek -> calday
+		if self.is_calendar(from) || self.is_calendar(to) {
+			return match (self.calendar_unit(from), self.calendar_unit(to)) {
+				(Some(f), Some(t)) if f.is_month_based() == t.is_month_based() => Ok(value * f.count() as f64 / t.count() as f64),
+				_ => Err(UnitError::AmbiguousCalendar(self.format_unit(from, &FormatOptions::DEFAULT), self.format_unit(to, &FormatOptions::DEFAULT))),
+			};
+		}
+
+		// Точные множители не копят ошибку: `1ft -> in` = 12, а не 12.000000000000002
+		if let Some(factor) = self.exact_scale(from).zip(self.exact_scale(to)).and_then(|(from, to)| from.checked_div(to)) {
+			return Ok(value * factor.numer() as f64 / factor.denom() as f64);
+		}
+
+		let base = match self.single(from) {
+			Some((prefix, def)) => def.normalize(value * prefix),
+			None => value * self.absolute_scale(from, relative)?,
+		};
+
+		Ok(match self.single(to) {
+			Some((prefix, def)) => def.denormalize(base) / prefix,
+			None => base / self.absolute_scale(to, relative)?,
+		})
+	}
+
+	/// Есть ли в юните множители, зависящие от вёрстки (em, rem, px)
+	pub fn is_relative(&self, unit: &ResolvedUnit) -> bool {
+		unit.factors.iter().any(|factor| self.definition(factor.unit).is_some_and(|def| matches!(def.props.mode, CalcMode::Relative(_))))
+	}
+
+	/// Дата плюс длительность: календарные юниты по правилам календаря, физические — точно
+	pub fn add_to_date(&self, date: CalendarDate, amount: f64, unit: &ResolvedUnit) -> Result<CalendarDate, UnitError> {
+		let overflow = || UnitError::IncompatibleUnits(date.to_string(), self.format_unit(unit, &FormatOptions::DEFAULT));
+
+		if let Some(calendar) = self.calendar_unit(unit) {
+			// Полтора месяца не имеют однозначного смысла
+			if amount.fract() != 0.0 {
+				return Err(UnitError::AmbiguousCalendar(format!("{}{}", amount, self.format_unit(unit, &FormatOptions::DEFAULT)), date.to_string()));
 			}
+			return date.add(amount as i64, calendar).ok_or_else(overflow);
+		}
+
+		// Физическая длительность добавляется только целыми сутками: `12h` к дате не прибавить
+		let second = self.resolve(b"s").ok_or_else(overflow)?;
+		let seconds = self.convert_in(amount, unit, &second, &RelativeContext::UNKNOWN)?;
+		if (seconds / 86400.0 - (seconds / 86400.0).round()).abs() > 1e-9 {
+			return Err(UnitError::AmbiguousCalendar(format!("{}{}", amount, self.format_unit(unit, &FormatOptions::DEFAULT)), date.to_string()));
+		}
+		date.add_seconds(seconds).ok_or_else(overflow)
+	}
+
+	/// Есть ли в юните календарные множители
+	pub fn is_calendar(&self, unit: &ResolvedUnit) -> bool {
+		unit.factors.iter().any(|factor| self.definition(factor.unit).is_some_and(|def| matches!(def.props.mode, CalcMode::Calendar(_))))
+	}
+
+	fn calendar_unit(&self, unit: &ResolvedUnit) -> Option<CalendarUnit> {
+		match unit.factors.as_slice() {
+			[single] if single.exponent == 1 => match self.definition(single.unit)?.props.mode {
+				CalcMode::Calendar(calendar) => Some(calendar),
+				_ => None,
+			},
+			_ => None,
+		}
+	}
+
+	// Одиночный юнит в первой степени: только у него имеют смысл offset и нелинейные режимы
+	fn single(&self, unit: &ResolvedUnit) -> Option<(f64, &UnitDef)> {
+		match unit.factors.as_slice() {
+			[single] if single.exponent == 1 => Some((single.prefix.map_or(1.0, |p| p.factor), self.definition(single.unit)?)).filter(|(_, def)| !matches!(def.props.mode, CalcMode::Relative(_))),
+			_ => None,
+		}
+	}
+
+	// Множитель до базовых единиц с подставленными базами вёрстки
+	fn absolute_scale(&self, unit: &ResolvedUnit, relative: &RelativeContext) -> Result<f64, UnitError> {
+		if !self.is_relative(unit) {
+			return Ok(unit.scale);
+		}
+
+		unit.factors.iter().try_fold(1.0, |scale, factor| {
+			let def = self.definition(factor.unit).ok_or(UnitError::UnknownUnit(format!("{:?}", factor.unit)))?;
+			let base = match def.props.mode {
+				CalcMode::Relative(to) => relative.base(to).ok_or_else(|| UnitError::Deferred(def.symbol.to_string()))?,
+				_ => 1.0,
+			};
+			Ok(scale * (factor.prefix.map_or(1.0, |p| p.factor) * def.props.scale * base).powi(i32::from(factor.exponent)))
+		})
+	}
+}
+
+// Герц — оборот в секунду: `rpm -> Hz`, `Hz -> rad/s`. Множитель от угловой скорости к частоте (или обратно) в базовых единицах
+fn cycle_factor(from: &ResolvedUnit, to: &ResolvedUnit) -> Option<f64> {
+	// Беккерели тоже 1/s, но распад — не вращение
+	let angular = Dimension::of(UnitKind::AngularVelocity);
+	if from.dimension == angular && to.kind == UnitKind::Frequency {
+		Some(std::f64::consts::TAU.recip())
+	} else if from.kind == UnitKind::Frequency && to.dimension == angular {
+		Some(std::f64::consts::TAU)
+	} else {
+		None
+	}
+}
+
+pub fn build_unit_tree(units: &[UnitDef]) -> UnitTree {
+	let mut tree = UnitTree::default();
+
+	for (index, unit) in units.iter().enumerate() {
+		if unit.symbol.is_empty() {
+			continue;
 		}
+
+		tree.insert(&unit.symbol, UnitId(index as u16));
 	}
 
 	tree
 }
+
+#[cfg(test)]
+impl UnitContext {
+	/// Для тестов: юнит по записи, с паникой на неразобранной
+	pub fn unit(&self, symbol: &str) -> ResolvedUnit {
+		self.resolve(symbol.as_bytes()).expect(symbol)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn table_scales_are_in_base_units() {
+		let ctx = UnitContext::new();
+		let close = |a: Option<f64>, b: f64| a.is_some_and(|a| (a - b).abs() <= b.abs() * 1e-12);
+
+		assert!(close(ctx.convert(1.0, &ctx.unit("kg"), &ctx.unit("g")), 1000.0));
+		assert!(close(ctx.convert(1.0, &ctx.unit("t"), &ctx.unit("kg")), 1000.0));
+		assert!(close(ctx.convert(1.0, &ctx.unit("Da"), &ctx.unit("kg")), 1.66053906660e-27));
+		assert!(close(ctx.convert(1.0, &ctx.unit("kWh"), &ctx.unit("kJ")), 3600.0));
+		assert!(close(ctx.convert(1.0, &ctx.unit("W*s"), &ctx.unit("J")), 1.0));
+	}
+
+	#[test]
+	fn composite_units_resolve() {
+		let ctx = UnitContext::new();
+
+		let kmh = ctx.unit("km/h");
+		assert_eq!(kmh.kind, UnitKind::Velocity);
+		assert!((kmh.scale - 1000.0 / 3600.0).abs() < 1e-12);
+
+		let ugm3 = ctx.unit("μg/m3");
+		assert_eq!(ugm3.kind, UnitKind::Density);
+		assert!((ugm3.scale - 1e-9).abs() < 1e-21);
+
+		let kws = ctx.unit("kW⋅s");
+		assert_eq!(kws.kind, UnitKind::Energy);
+		assert!((kws.scale - 1000.0).abs() < 1e-9);
+	}
+
+	#[test]
+	fn photometric_kinds() {
+		let ctx = UnitContext::new();
+		let kind = |s: &str| ctx.resolve(s.as_bytes()).map(|unit| unit.kind);
+
+		assert_eq!(kind("cd"), Some(UnitKind::LuminousIntensity));
+		assert_eq!(kind("lm"), Some(UnitKind::LuminousFlux));
+		assert_eq!(kind("lx"), Some(UnitKind::Illuminance));
+		assert_eq!(kind("lm/m2"), Some(UnitKind::Illuminance));
+	}
+
+	#[test]
+	fn registry_is_shared() {
+		fn assert_send_sync<T: Send + Sync>() {}
+		assert_send_sync::<UnitRegistry>();
+		assert_send_sync::<UnitContext>();
+
+		let handles: Vec<_> = (0..4).map(|_| std::thread::spawn(|| UnitContext::new().registry as *const UnitRegistry as usize)).collect();
+		let registries: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+		assert!(registries.windows(2).all(|w| w[0] == w[1]));
+	}
+
+	#[test]
+	fn long_names_resolve() {
+		let ctx = UnitContext::new();
+		assert_eq!(ctx.unit("meters"), ctx.unit("m"));
+		assert_eq!(ctx.unit("kilograms").scale, 1.0);
+		assert_eq!(ctx.unit("минут"), ctx.unit("min"));
+		assert!((ctx.unit("километров/час").scale - 1000.0 / 3600.0).abs() < 1e-12);
+		assert_eq!(ctx.find("секунду".as_bytes()), ctx.find(b"s"));
+		assert!(ctx.resolve(b"kilom").is_none());
+	}
+}
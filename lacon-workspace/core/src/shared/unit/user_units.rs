@@ -0,0 +1,115 @@
+use super::{Dimension, PrefixGroup, UnitDef, UnitKind};
+use std::collections::HashMap;
+
+/// От чего отсчитывается пользовательский юнит
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnitBase {
+	/// Существующая величина: `declare unit cell: Length`
+	Kind(UnitKind),
+	/// Новая базовая величина: `declare unit requisition: Requisition`
+	Dimension(String),
+	/// Другой юнит или юнит-выражение: `declare unit tick = 0.05 s`
+	Unit(String),
+}
+
+/// Объявление юнита из исходника или от хоста.
+/// Значение в новом юните переводится в `base` как `value * scale + offset`
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitDeclaration {
+	pub symbol: String,
+	pub base: UnitBase,
+	pub scale: f64,
+	pub offset: f64,
+	pub prefix_group: PrefixGroup,
+}
+
+impl UnitDeclaration {
+	pub fn new(symbol: impl Into<String>, base: UnitBase) -> Self {
+		Self {
+			symbol: symbol.into(),
+			base,
+			scale: 1.0,
+			offset: 0.0,
+			prefix_group: PrefixGroup::None,
+		}
+	}
+
+	pub fn with_scale(mut self, scale: f64) -> Self {
+		self.scale = scale;
+		self
+	}
+
+	pub fn with_offset(mut self, offset: f64) -> Self {
+		self.offset = offset;
+		self
+	}
+
+	pub fn with_prefixes(mut self, prefix_group: PrefixGroup) -> Self {
+		self.prefix_group = prefix_group;
+		self
+	}
+}
+
+/// Зарегистрированный пользовательский юнит: `UnitId` идут сразу после `UNITS`
+#[derive(Debug, Clone)]
+pub struct UserUnit {
+	pub def: UnitDef,
+	// Для новых величин `def.dimension` = `UnitKind::None`, настоящая размерность — здесь
+	pub dimension: Dimension,
+}
+
+/// Объявления юнитов по файлам проекта. Хост сканирует файл с объявлениями, кладёт сюда `Scanner::declarations`
+/// и передаёт библиотеку сканерам файлов с `import units from "./game.lacon"`
+#[derive(Debug, Clone, Default)]
+pub struct UnitLibrary {
+	files: HashMap<String, Vec<UnitDeclaration>>,
+}
+
+impl UnitLibrary {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Путь — как он записан в `import`; повторная вставка заменяет объявления файла
+	pub fn insert(&mut self, path: impl Into<String>, declarations: Vec<UnitDeclaration>) {
+		self.files.insert(path.into(), declarations);
+	}
+
+	pub fn get(&self, path: &str) -> Option<&[UnitDeclaration]> {
+		self.files.get(path).map(Vec::as_slice)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::shared::{UnitContext, UnitError};
+
+	#[test]
+	fn declarations_do_not_shadow_builtins() {
+		let mut ctx = UnitContext::new();
+		assert!(ctx.declare(UnitDeclaration::new("requisition", UnitBase::Dimension("Requisition".into())).with_prefixes(PrefixGroup::SI)).is_ok());
+		assert!(ctx.declare(UnitDeclaration::new("km", UnitBase::Kind(UnitKind::Length))).is_err());
+	}
+
+	#[test]
+	fn symbols_may_not_contain_digits() {
+		let mut ctx = UnitContext::new();
+		for symbol in ["m2x", "2x", "x2", "a b", "a/b"] {
+			assert!(matches!(ctx.declare(UnitDeclaration::new(symbol, UnitBase::Unit("m".into())).with_scale(2.0)), Err(UnitError::InvalidSymbol(_))), "{}", symbol);
+		}
+	}
+
+	#[test]
+	fn new_dimensions_are_distinct() {
+		let mut ctx = UnitContext::new();
+		ctx.declare(UnitDeclaration::new("requisition", UnitBase::Dimension("Requisition".into())).with_prefixes(PrefixGroup::SI)).expect("requisition");
+		ctx.declare(UnitDeclaration::new("tick", UnitBase::Unit("s".into())).with_scale(0.05)).expect("tick");
+		ctx.declare(UnitDeclaration::new("cell", UnitBase::Dimension("Cell".into())).with_prefixes(PrefixGroup::SI)).expect("cell");
+
+		let rate = ctx.unit("kcell/tick");
+		assert_eq!(rate.kind, UnitKind::None);
+		assert_ne!(rate.dimension, ctx.unit("krequisition/tick").dimension);
+		assert!((rate.scale - 20_000.0).abs() < 1e-9);
+	}
+}
@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod lexer_tests {
 	use lacon_core::frontend::lexer::{Scanner, TokenKind};
-	use lacon_core::shared::{ErrorReporter, ErrorStorage, SourceFile, UnitArena, UnitContext};
+	use lacon_core::shared::{ErrorReporter, ErrorStorage, SourceFile, UnitContext};
 	use memory_stats::memory_stats;
 	use std::path::PathBuf;
 	use std::time::Instant;
@@ -9,8 +9,7 @@ mod lexer_tests {
 	#[test]
 	fn lexer_speed_test_full() {
 		let mut error_store = ErrorStorage::new();
-		let arena = UnitArena::new();
-		let ctx = UnitContext::new(&arena);
+		let ctx = UnitContext::new();
 
 		let path = PathBuf::from("../files/big.lacon");
 		let source_file = SourceFile::load(path).unwrap();
@@ -123,7 +122,7 @@ mod lexer_tests {
 			if index >= 5 {
 				break;
 			}
-			ErrorReporter::Console.report(&error.error);
+			ErrorReporter::Console.report(&error.error).expect("console is writable");
 		}
 	}
 }
@@ -3,7 +3,7 @@ mod lexer_tests {
 	use super::super::{LACON_FILES_DIR, LEXER_RESULTS_DIR};
 	use lacon_core::frontend::lexer::Scanner;
 	use lacon_core::frontend::lexer::TokenFlags;
-	use lacon_core::shared::{ErrorStorage, SourceCodeReadModes, SourceFile, UnitArena, UnitContext};
+	use lacon_core::shared::{ErrorStorage, SourceCodeReadModes, SourceFile, UnitContext};
 	use memory_stats::memory_stats;
 	use std::fs::{self, File};
 	use std::io::{BufWriter, Write};
@@ -23,8 +23,7 @@ mod lexer_tests {
 		let mut processed_count_static = 0;
 		let mut total_scan_time = Duration::ZERO;
 
-		let arena = UnitArena::new();
-		let ctx = UnitContext::new(&arena);
+		let ctx = UnitContext::new();
 
 		for entry in entries {
 			let entry = entry.expect("Ошибка элемента директории");